@@ -0,0 +1,876 @@
+use std::{
+    path::{Path, PathBuf},
+    str::CharIndices,
+};
+
+use crate::{
+    dart::{class::ClassMember, func_call::FuncCall, var::VarModifier, Annotation, Expr, Var},
+    parser::skip::block_end,
+    Dart, Program, Resolution, Symbol, Token, TokenKind, Tokenizer,
+};
+
+/// The value of a constant expression.
+#[derive(PartialEq, Clone, Debug)]
+pub enum ConstValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Double(f64),
+    String(String),
+    List(Vec<ConstValue>),
+    Set(Vec<ConstValue>),
+    Map(Vec<(ConstValue, ConstValue)>),
+    /// A value of an enum, e.g. `FieldRename.snake`.
+    EnumValue {
+        library: PathBuf,
+        enum_name: String,
+        value: String,
+    },
+    /// A const constructor invocation with the evaluated arguments,
+    /// e.g. `Duration(seconds: 1)`.
+    Object {
+        /// The type (and the constructor) as written, e.g. `Foo.named`.
+        constructor: String,
+        args: Vec<(Option<String>, ConstValue)>,
+    },
+}
+
+/// The value of an annotation argument, or why it cannot be evaluated.
+pub type ConstArg<'s> = (Option<&'s str>, Result<ConstValue, String>);
+
+/// Evaluates constant expressions, e.g. the arguments of annotations,
+/// resolving the identifiers to the `const` variables across libraries.
+///
+/// Supported are literals (including adjacent strings and interpolations),
+/// lists, sets and maps, arithmetic and string concatenation, enum values,
+/// and const constructor invocations, which are evaluated to their arguments.
+#[derive(Clone, Copy, Debug)]
+pub struct ConstEvaluator<'a> {
+    program: &'a Program,
+}
+
+/// The limit on the nesting of references, interpolations and brackets,
+/// deeper references are considered to be cyclic.
+const MAX_DEPTH: usize = 64;
+
+impl<'a> ConstEvaluator<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Self { program }
+    }
+
+    /// Evaluate an expression occurring in the library at `path`.
+    pub fn eval(&self, path: &Path, expr: &Expr) -> Result<ConstValue, String> {
+        self.eval_expr(path, expr, 0).map_err(|e| match expr {
+            Expr::Ident(s) | Expr::Verbatim(s) => format!("Cannot evaluate `{s}`: {e}"),
+            Expr::String(s) => format!("Cannot evaluate `'{s}'`: {e}"),
+        })
+    }
+
+    /// Evaluate the arguments of an annotation occurring in the library
+    /// at `path`, each argument separately.
+    ///
+    /// An annotation without arguments (e.g. `@defaults`) refers
+    /// to a constant, which is evaluated as the only argument.
+    pub fn eval_annotation<'s>(
+        &self,
+        path: &Path,
+        annotation: &Annotation<'s>,
+    ) -> Vec<ConstArg<'s>> {
+        match annotation {
+            Annotation::Ident(name) => vec![(None, self.eval(path, &Expr::Ident(name)))],
+            Annotation::FuncCall(func_call) => self.eval_args(path, func_call),
+        }
+    }
+
+    pub fn eval_args<'s>(&self, path: &Path, func_call: &FuncCall<'s>) -> Vec<ConstArg<'s>> {
+        func_call
+            .args
+            .iter()
+            .map(|arg| (arg.name, self.eval(path, &arg.value)))
+            .collect()
+    }
+
+    fn eval_expr(&self, path: &Path, expr: &Expr, depth: usize) -> Result<ConstValue, String> {
+        match expr {
+            Expr::Ident(name) => self.eval_ident(path, name, depth),
+            Expr::String(body) => self.eval_string_body(path, body, false, depth),
+            Expr::Verbatim(s) => self.eval_source(path, s, depth),
+        }
+    }
+
+    fn eval_source(&self, path: &Path, s: &str, depth: usize) -> Result<ConstValue, String> {
+        let tokens = Tokenizer::new(s.as_bytes())
+            .filter_map(Result::ok)
+            .filter(|token| !matches!(token.kind, TokenKind::Whitespace | TokenKind::Comment))
+            .collect();
+        let mut parser = ExprParser {
+            evaluator: *self,
+            path,
+            depth,
+            tokens,
+            pos: 0,
+        };
+
+        let value = parser.expr()?;
+        match parser.peek() {
+            Some(token) => Err(format!("unexpected `{}`", token.text)),
+            None => Ok(value),
+        }
+    }
+
+    /// Resolve a possibly prefixed or qualified identifier, e.g. `kIdKey`,
+    /// `json.FieldRename.snake` or `Config.defaultName`.
+    fn eval_ident(&self, path: &Path, name: &str, depth: usize) -> Result<ConstValue, String> {
+        if depth >= MAX_DEPTH {
+            return Err("the constants are nested too deeply or are cyclic".to_owned());
+        }
+
+        let parts = name.split('.').collect::<Vec<_>>();
+        let (symbol, rest) = match self.lookup(path, parts[0])? {
+            Some(symbol) => (symbol, &parts[1..]),
+            None if parts.len() > 1 => match self.lookup(path, &parts[..2].join("."))? {
+                Some(symbol) => (symbol, &parts[2..]),
+                None => return Err(format!("cannot resolve `{}`", parts[..2].join("."))),
+            },
+            None => return Err(format!("cannot resolve `{name}`")),
+        };
+        let library = symbol.library.path();
+
+        match (symbol.declaration.as_ref(), rest) {
+            (Dart::Var(var), []) => self.eval_var(library, var, depth),
+            (Dart::Enum(enum_ty), [value]) => {
+                if enum_ty.values.iter().any(|v| v.as_ref().name == *value) {
+                    Ok(ConstValue::EnumValue {
+                        library: library.to_owned(),
+                        enum_name: enum_ty.name.to_owned(),
+                        value: value.to_string(),
+                    })
+                } else {
+                    Err(format!(
+                        "`{value}` is not a value of the enum `{}`",
+                        enum_ty.name
+                    ))
+                }
+            }
+            (Dart::Class(class), [field]) => {
+                let var = class.body.iter().find_map(|member| match member.as_ref() {
+                    ClassMember::Var(var) if var.name == *field => Some(var),
+                    _ => None,
+                });
+                match var {
+                    Some(var) if var.modifiers.contains(VarModifier::Static) => {
+                        self.eval_var(library, var, depth)
+                    }
+                    _ => Err(format!(
+                        "`{field}` is not a static field of the class `{}`",
+                        class.name
+                    )),
+                }
+            }
+            _ => Err(format!("`{name}` is not a constant")),
+        }
+    }
+
+    fn eval_var(&self, path: &Path, var: &Var, depth: usize) -> Result<ConstValue, String> {
+        if !var.modifiers.contains(VarModifier::Const) {
+            return Err(format!("`{}` is not a const variable", var.name));
+        }
+
+        match &var.initializer {
+            Some(initializer) => self.eval_expr(path, initializer, depth + 1),
+            None => Err(format!("`{}` has no initializer", var.name)),
+        }
+    }
+
+    /// Whether a name refers to a top-level function rather than to a class
+    /// (e.g. in `f()`), with the prefix, if any.
+    fn is_func(&self, path: &Path, name: &str) -> Result<bool, String> {
+        let mut parts = name.splitn(3, '.');
+        let first = parts.next().unwrap_or(name);
+        let symbol = match (self.lookup(path, first)?, parts.next()) {
+            (Some(symbol), _) => Some(symbol),
+            (None, Some(second)) => self.lookup(path, &format!("{first}.{second}"))?,
+            (None, None) => None,
+        };
+
+        Ok(symbol.is_some_and(|symbol| matches!(symbol.declaration.as_ref(), Dart::FuncLike(_))))
+    }
+
+    fn lookup(&self, path: &Path, name: &str) -> Result<Option<Symbol<'a>>, String> {
+        match self.program.lookup(path, name)? {
+            Resolution::Declared(symbol) => Ok(Some(symbol)),
+            Resolution::Ambiguous(_) => Err(format!("`{name}` is ambiguous")),
+            Resolution::Unresolved => Ok(None),
+        }
+    }
+
+    /// Decode the body of a string literal (without the quotes), evaluating
+    /// the interpolations unless it's raw.
+    fn eval_string_body(
+        &self,
+        path: &Path,
+        body: &str,
+        is_raw: bool,
+        depth: usize,
+    ) -> Result<ConstValue, String> {
+        if is_raw {
+            return Ok(ConstValue::String(body.to_owned()));
+        }
+
+        let mut s = String::with_capacity(body.len());
+        let mut chars = body.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    let Some((_, c)) = chars.next() else {
+                        return Err("unterminated escape sequence".to_owned());
+                    };
+                    let decoded = match c {
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'v' => '\u{b}',
+                        'x' | 'u' => {
+                            let rest = &body[i + 2..];
+                            let (hex, len) = match (c, rest.strip_prefix('{')) {
+                                ('u', Some(braced)) => {
+                                    let end = braced.find('}').ok_or("invalid escape sequence")?;
+                                    (&braced[..end], end + 2)
+                                }
+                                ('u', None) => (rest.get(..4).ok_or("invalid escape sequence")?, 4),
+                                _ => (rest.get(..2).ok_or("invalid escape sequence")?, 2),
+                            };
+                            let decoded = u32::from_str_radix(hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or("invalid escape sequence")?;
+                            skip_to(&mut chars, i + 2 + len);
+                            decoded
+                        }
+                        c => c,
+                    };
+                    s.push(decoded);
+                }
+                '$' => {
+                    let rest = &body[i + 1..];
+                    let (expr, len) = if rest.starts_with('{') {
+                        let len = block_end(&rest.as_bytes()[1..], MAX_DEPTH)
+                            .ok_or("unterminated interpolation")?;
+                        (Expr::Verbatim(&rest[1..len]), len + 1)
+                    } else {
+                        let len = rest
+                            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                            .unwrap_or(rest.len());
+                        if len == 0 {
+                            return Err("invalid interpolation".to_owned());
+                        }
+                        (Expr::Ident(&rest[..len]), len)
+                    };
+                    let value = self.eval_expr(path, &expr, depth + 1)?;
+                    s.push_str(&interpolate(&value)?);
+                    skip_to(&mut chars, i + 1 + len);
+                }
+                c => s.push(c),
+            }
+        }
+
+        Ok(ConstValue::String(s))
+    }
+}
+
+/// A recursive descent parser that evaluates an expression as it goes.
+struct ExprParser<'a, 'p, 't> {
+    evaluator: ConstEvaluator<'a>,
+    path: &'p Path,
+    depth: usize,
+    tokens: Vec<Token<'t>>,
+    pos: usize,
+}
+
+impl<'a, 'p, 't> ExprParser<'a, 'p, 't> {
+    fn expr(&mut self) -> Result<ConstValue, String> {
+        let mut value = self.term()?;
+
+        while let Some(op) = self.eat_any(&["+", "-"]) {
+            let rhs = self.term()?;
+            value = match (op, value, rhs) {
+                ("+", ConstValue::String(a), ConstValue::String(b)) => ConstValue::String(a + &b),
+                (op, a, b) => arithmetic(op, a, b)?,
+            };
+        }
+
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<ConstValue, String> {
+        let mut value = self.unary()?;
+
+        loop {
+            let op = if self.eat("~") {
+                self.expect("/")?;
+                "~/"
+            } else if let Some(op) = self.eat_any(&["*", "/", "%"]) {
+                op
+            } else {
+                break;
+            };
+            let rhs = self.unary()?;
+            value = arithmetic(op, value, rhs)?;
+        }
+
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<ConstValue, String> {
+        let mut ops = Vec::new();
+        while let Some(op) = self.eat_any(&["-", "!"]) {
+            ops.push(op);
+        }
+
+        // The innermost operator applies first
+        ops.into_iter()
+            .rev()
+            .try_fold(self.primary()?, |value, op| match (op, value) {
+                ("-", ConstValue::Int(i)) => {
+                    i.checked_neg().map(ConstValue::Int).ok_or(OVERFLOW.into())
+                }
+                ("-", ConstValue::Double(d)) => Ok(ConstValue::Double(-d)),
+                ("-", _) => Err("`-` applies to numbers only".to_owned()),
+                (_, ConstValue::Bool(b)) => Ok(ConstValue::Bool(!b)),
+                _ => Err("`!` applies to booleans only".to_owned()),
+            })
+    }
+
+    /// A primary expression, the brackets in it count towards `MAX_DEPTH`.
+    fn primary(&mut self) -> Result<ConstValue, String> {
+        if self.depth >= MAX_DEPTH {
+            return Err("the constants are nested too deeply or are cyclic".to_owned());
+        }
+
+        self.depth += 1;
+        let result = self.primary_unbounded();
+        self.depth -= 1;
+
+        result
+    }
+
+    fn primary_unbounded(&mut self) -> Result<ConstValue, String> {
+        let token = self.next().ok_or("unexpected end of expression")?;
+
+        match (token.kind, token.text) {
+            (TokenKind::Identifier, "const") => self.primary(),
+            (TokenKind::Identifier, "true") => Ok(ConstValue::Bool(true)),
+            (TokenKind::Identifier, "false") => Ok(ConstValue::Bool(false)),
+            (TokenKind::Identifier, "null") => Ok(ConstValue::Null),
+            (TokenKind::Identifier, _) => self.ident(token.text),
+            (TokenKind::Number, text) => number(text),
+            (TokenKind::String, text) => {
+                let mut s = self.string(text)?;
+                // Adjacent string literals are concatenated
+                while let Some(token) = self.peek().filter(|t| t.kind == TokenKind::String) {
+                    self.pos += 1;
+                    s.push_str(&self.string(token.text)?);
+                }
+                Ok(ConstValue::String(s))
+            }
+            (TokenKind::Punct, "(") => {
+                let value = self.expr()?;
+                self.expect(")")?;
+                Ok(value)
+            }
+            (TokenKind::Punct, "<") => {
+                self.skip_type_args()?;
+                self.primary()
+            }
+            (TokenKind::Punct, "[") => {
+                let items = self.items("]", Self::expr)?;
+                Ok(ConstValue::List(items))
+            }
+            (TokenKind::Punct, "{") => self.set_or_map(),
+            (_, text) => Err(format!("unsupported `{text}`")),
+        }
+    }
+
+    /// An identifier, possibly qualified, or a constructor invocation.
+    fn ident(&mut self, first: &str) -> Result<ConstValue, String> {
+        let mut name = first.to_owned();
+        while self.eat(".") {
+            let part = self
+                .next()
+                .filter(|token| token.kind == TokenKind::Identifier)
+                .ok_or("expected an identifier after `.`")?;
+            name.push('.');
+            name.push_str(part.text);
+        }
+
+        if self.peek().is_some_and(|token| token.text == "<") {
+            self.pos += 1;
+            self.skip_type_args()?;
+        }
+
+        if self.eat("(") {
+            if self.evaluator.is_func(self.path, &name)? {
+                return Err(format!("`{name}` is a function, which is not constant"));
+            }
+            let args = self.items(")", |parser| {
+                let is_named = parser
+                    .peek()
+                    .is_some_and(|t| t.kind == TokenKind::Identifier)
+                    && parser
+                        .tokens
+                        .get(parser.pos + 1)
+                        .is_some_and(|t| t.text == ":");
+                let name = if is_named {
+                    let name = parser.tokens[parser.pos].text.to_owned();
+                    parser.pos += 2;
+                    Some(name)
+                } else {
+                    None
+                };
+                Ok((name, parser.expr()?))
+            })?;
+            return Ok(ConstValue::Object {
+                constructor: name,
+                args,
+            });
+        }
+
+        self.evaluator.eval_ident(self.path, &name, self.depth)
+    }
+
+    fn set_or_map(&mut self) -> Result<ConstValue, String> {
+        let mut is_map = None;
+        let entries = self.items("}", |parser| {
+            let key = parser.expr()?;
+            let value = if parser.eat(":") {
+                Some(parser.expr()?)
+            } else {
+                None
+            };
+            match (is_map, value.is_some()) {
+                (Some(is_map), has_value) if is_map != has_value => {
+                    Err("mixed set and map entries".to_owned())
+                }
+                _ => {
+                    is_map = Some(value.is_some());
+                    Ok((key, value))
+                }
+            }
+        })?;
+
+        // An empty `{}` is a map
+        if is_map.unwrap_or(true) {
+            Ok(ConstValue::Map(
+                entries
+                    .into_iter()
+                    .filter_map(|(key, value)| Some((key, value?)))
+                    .collect(),
+            ))
+        } else {
+            Ok(ConstValue::Set(
+                entries.into_iter().map(|(key, _)| key).collect(),
+            ))
+        }
+    }
+
+    /// Comma-separated items up to the closing bracket, with an optional
+    /// trailing comma.
+    fn items<T>(
+        &mut self,
+        close: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, String>,
+    ) -> Result<Vec<T>, String> {
+        let mut items = Vec::new();
+
+        while !self.eat(close) {
+            if self.peek().is_some_and(|token| token.text == ".") {
+                return Err("spread elements are not supported".to_owned());
+            }
+            items.push(item(self)?);
+            if !self.eat(",") {
+                self.expect(close)?;
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn skip_type_args(&mut self) -> Result<(), String> {
+        let mut depth = 1;
+
+        while depth > 0 {
+            match self.next().ok_or("unterminated type arguments")?.text {
+                "<" => depth += 1,
+                ">" => depth -= 1,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn string(&self, text: &str) -> Result<String, String> {
+        let (is_raw, text) = match text.strip_prefix('r') {
+            Some(text) => (true, text),
+            None => (false, text),
+        };
+        let quote_len = if text.starts_with("'''") || text.starts_with("\"\"\"") {
+            3
+        } else {
+            1
+        };
+        let body = text
+            .get(quote_len..text.len().saturating_sub(quote_len))
+            .filter(|_| text.len() >= 2 * quote_len)
+            .ok_or("unterminated string")?;
+        // The first line break of a multiline string is ignored
+        let body = match quote_len {
+            3 => body
+                .strip_prefix("\r\n")
+                .or_else(|| body.strip_prefix('\n'))
+                .unwrap_or(body),
+            _ => body,
+        };
+
+        match self
+            .evaluator
+            .eval_string_body(self.path, body, is_raw, self.depth)?
+        {
+            ConstValue::String(s) => Ok(s),
+            _ => unreachable!("Not a string"),
+        }
+    }
+
+    fn peek(&self) -> Option<Token<'t>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<Token<'t>> {
+        let token = self.peek()?;
+        self.pos += 1;
+
+        Some(token)
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        self.eat_any(&[text]).is_some()
+    }
+
+    fn eat_any<'o>(&mut self, options: &[&'o str]) -> Option<&'o str> {
+        let token = self.peek()?;
+        let option = options
+            .iter()
+            .find(|option| token.kind == TokenKind::Punct && token.text == **option)?;
+        self.pos += 1;
+
+        Some(option)
+    }
+
+    fn expect(&mut self, text: &str) -> Result<(), String> {
+        if self.eat(text) {
+            Ok(())
+        } else {
+            match self.peek() {
+                Some(token) => Err(format!("expected `{text}`, found `{}`", token.text)),
+                None => Err(format!("expected `{text}`")),
+            }
+        }
+    }
+}
+
+const OVERFLOW: &str = "integer overflow";
+
+fn number(text: &str) -> Result<ConstValue, String> {
+    let text = text.replace('_', "");
+    let invalid = || format!("invalid number `{text}`");
+
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        return u64::from_str_radix(hex, 16)
+            .map(|i| ConstValue::Int(i as i64))
+            .map_err(|_| invalid());
+    }
+
+    if text.contains(['.', 'e', 'E']) {
+        text.parse().map(ConstValue::Double).map_err(|_| invalid())
+    } else {
+        text.parse().map(ConstValue::Int).map_err(|_| invalid())
+    }
+}
+
+fn arithmetic(op: &str, a: ConstValue, b: ConstValue) -> Result<ConstValue, String> {
+    use ConstValue::{Double, Int};
+
+    let value = match (a, b) {
+        (Int(a), Int(b)) => match op {
+            "+" => Int(a.checked_add(b).ok_or(OVERFLOW)?),
+            "-" => Int(a.checked_sub(b).ok_or(OVERFLOW)?),
+            "*" => Int(a.checked_mul(b).ok_or(OVERFLOW)?),
+            "/" => Double(a as f64 / b as f64),
+            "~/" => Int(a.checked_div(b).ok_or("integer division by zero")?),
+            "%" => Int(a.checked_rem_euclid(b).ok_or("integer division by zero")?),
+            _ => unreachable!("Unknown operator"),
+        },
+        (a @ (Int(_) | Double(_)), b @ (Int(_) | Double(_))) => {
+            let as_f64 = |value| match value {
+                Int(i) => i as f64,
+                Double(d) => d,
+                _ => unreachable!("Not a number"),
+            };
+            let (a, b) = (as_f64(a), as_f64(b));
+            match op {
+                "+" => Double(a + b),
+                "-" => Double(a - b),
+                "*" => Double(a * b),
+                "/" => Double(a / b),
+                "~/" => Int((a / b).trunc() as i64),
+                "%" => Double(a.rem_euclid(b)),
+                _ => unreachable!("Unknown operator"),
+            }
+        }
+        _ => {
+            return Err(format!(
+                "`{op}` applies to numbers (or `+` to strings) only"
+            ))
+        }
+    };
+
+    Ok(value)
+}
+
+/// Advance the characters up to the byte offset `end`.
+fn skip_to(chars: &mut CharIndices, end: usize) {
+    while chars.offset() < end && chars.next().is_some() {}
+}
+
+/// The string representation of a value in an interpolation.
+fn interpolate(value: &ConstValue) -> Result<String, String> {
+    match value {
+        ConstValue::Null => Ok("null".to_owned()),
+        ConstValue::Bool(b) => Ok(b.to_string()),
+        ConstValue::Int(i) => Ok(i.to_string()),
+        // Dart prints the integral doubles with a fraction (`1.0`)
+        ConstValue::Double(d) if d.is_finite() && d.fract() == 0.0 => Ok(format!("{d:.1}")),
+        ConstValue::Double(d) => Ok(d.to_string()),
+        ConstValue::String(s) => Ok(s.clone()),
+        _ => Err("only numbers, strings, booleans and null can be interpolated".to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::{dart::meta::Meta, Package, PackageConfig, ParsedSource};
+
+    const FILES: [(&str, &str); 2] = [
+        (
+            "app/lib/main.dart",
+            "import 'package:models/keys.dart';\n\
+             import 'package:models/keys.dart' as k;\n\
+             @JsonSerializable(\n\
+               fieldRename: FieldRename.snake,\n\
+               name: kNameKey,\n\
+               id: k.kIdKey,\n\
+               adjacent: 'a' r'\\n' \"\\u{1F600}\",\n\
+               raw: r'$type\\n',\n\
+               field: Config.defaultName,\n\
+               number: 7 ~/ 2 * -0x10 + 1.5,\n\
+               list: kList,\n\
+               map: kMap,\n\
+               set: kSet,\n\
+               config: defaults,\n\
+               notConst: notConst,\n\
+               call: f(),\n\
+             constructor: k.Config(),\n\
+               cycle: kCycle,\n\
+               unknown: kUnknown,\n\
+             )\n\
+             class User {}\n",
+        ),
+        (
+            "models/lib/keys.dart",
+            "const kIdKey = 'id';\n\
+             const kPrefix = 'user_';\n\
+             const String kNameKey = kPrefix + 'name';\n\
+             const kList = [1, 2, -3,];\n\
+             const kMap = {'a': 1.5, 'b': null};\n\
+             const kSet = <String>{'\\$kIdKey:${kList}', '$kIdKey${1 + 1.0}'};\n\
+             const defaults = MyConfig(true, key: FieldRename.none);\n\
+             final notConst = 1;\n\
+             int f() => 1;\n\
+             const kCycle = kCycle2;\n\
+             const kCycle2 = kCycle;\n\
+             enum FieldRename { none, snake }\n\
+             class Config {\n  static const defaultName = 'cfg_${kIdKey}';\n}\n",
+        ),
+    ];
+
+    fn program() -> Program {
+        let files = FILES
+            .iter()
+            .map(|(path, source)| (PathBuf::from(path), source.to_string()))
+            .collect::<HashMap<_, _>>();
+        let config = PackageConfig {
+            packages: vec![Package {
+                name: "models".to_owned(),
+                root: PathBuf::from("models"),
+                lib: PathBuf::from("models/lib"),
+                language_version: None,
+            }],
+        };
+
+        Program::load([PathBuf::from("app/lib/main.dart")], &config, |path| {
+            ParsedSource::parse(files[path].clone()).map_err(|err| err.to_string())
+        })
+        .unwrap()
+    }
+
+    fn string(s: &str) -> ConstValue {
+        ConstValue::String(s.to_owned())
+    }
+
+    #[test]
+    fn eval_annotation_test() {
+        let program = program();
+        let path = Path::new("app/lib/main.dart");
+        let class = program
+            .library(path)
+            .unwrap()
+            .declarations()
+            .next()
+            .unwrap();
+        let Meta::Annotation(annotation) = &class.meta[0] else {
+            panic!("Not an annotation");
+        };
+
+        let args = ConstEvaluator::new(&program).eval_annotation(path, annotation);
+        let args = args.into_iter().collect::<HashMap<_, _>>();
+
+        assert_eq!(
+            args[&Some("fieldRename")],
+            Ok(ConstValue::EnumValue {
+                library: PathBuf::from("models/lib/keys.dart"),
+                enum_name: "FieldRename".to_owned(),
+                value: "snake".to_owned(),
+            })
+        );
+        assert_eq!(args[&Some("name")], Ok(string("user_name")));
+        assert_eq!(args[&Some("id")], Ok(string("id")));
+        assert_eq!(args[&Some("adjacent")], Ok(string("a\\n\u{1F600}")));
+        assert_eq!(args[&Some("raw")], Ok(string("$type\\n")));
+        assert_eq!(args[&Some("field")], Ok(string("cfg_id")));
+        assert_eq!(args[&Some("number")], Ok(ConstValue::Double(-46.5)));
+        assert_eq!(
+            args[&Some("list")],
+            Ok(ConstValue::List(vec![
+                ConstValue::Int(1),
+                ConstValue::Int(2),
+                ConstValue::Int(-3)
+            ]))
+        );
+        assert_eq!(
+            args[&Some("map")],
+            Ok(ConstValue::Map(vec![
+                (string("a"), ConstValue::Double(1.5)),
+                (string("b"), ConstValue::Null)
+            ]))
+        );
+        assert_eq!(
+            args[&Some("set")],
+            Err(
+                "Cannot evaluate `kSet`: only numbers, strings, booleans and null can be \
+                 interpolated"
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            args[&Some("config")],
+            Ok(ConstValue::Object {
+                constructor: "MyConfig".to_owned(),
+                args: vec![
+                    (None, ConstValue::Bool(true)),
+                    (
+                        Some("key".to_owned()),
+                        ConstValue::EnumValue {
+                            library: PathBuf::from("models/lib/keys.dart"),
+                            enum_name: "FieldRename".to_owned(),
+                            value: "none".to_owned(),
+                        }
+                    ),
+                ],
+            })
+        );
+        assert_eq!(
+            args[&Some("notConst")],
+            Err("Cannot evaluate `notConst`: `notConst` is not a const variable".to_owned())
+        );
+        assert_eq!(
+            args[&Some("call")],
+            Err("Cannot evaluate `f()`: `f` is a function, which is not constant".to_owned())
+        );
+        assert_eq!(
+            args[&Some("constructor")],
+            Ok(ConstValue::Object {
+                constructor: "k.Config".to_owned(),
+                args: vec![],
+            })
+        );
+        assert!(args[&Some("cycle")]
+            .as_ref()
+            .is_err_and(|e| e.contains("cyclic")));
+        assert_eq!(
+            args[&Some("unknown")],
+            Err("Cannot evaluate `kUnknown`: cannot resolve `kUnknown`".to_owned())
+        );
+    }
+
+    #[test]
+    fn eval_interpolation_test() {
+        let program = program();
+        let evaluator = ConstEvaluator::new(&program);
+        let path = Path::new("models/lib/keys.dart");
+
+        assert_eq!(
+            evaluator.eval(path, &Expr::Verbatim("'\\$kIdKey:${kList.length}'")),
+            Err(
+                "Cannot evaluate `'\\$kIdKey:${kList.length}'`: `kList.length` is not a constant"
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            evaluator.eval(path, &Expr::Verbatim("'$kIdKey${1 + 1.0}${null}'")),
+            Ok(string("id2.0null"))
+        );
+        assert_eq!(
+            evaluator.eval(path, &Expr::String("\\$kIdKey")),
+            Ok(string("$kIdKey"))
+        );
+        assert_eq!(
+            evaluator.eval(path, &Expr::Verbatim("'${'éé'}xy\\u{e9}z'")),
+            Ok(string("ééxyéz"))
+        );
+    }
+
+    #[test]
+    fn eval_nested_test() {
+        let program = program();
+        let evaluator = ConstEvaluator::new(&program);
+        let path = Path::new("models/lib/keys.dart");
+
+        let negated = format!("{}1", "-".repeat(200_001));
+        assert_eq!(
+            evaluator.eval(path, &Expr::Verbatim(&negated)),
+            Ok(ConstValue::Int(-1))
+        );
+        let nested = format!("{}1{}", "(".repeat(200_000), ")".repeat(200_000));
+        assert!(evaluator
+            .eval(path, &Expr::Verbatim(&nested))
+            .is_err_and(|e| e.contains("nested too deeply")));
+        let nested = format!("{}1{}", "[".repeat(32), "]".repeat(32));
+        assert!(evaluator.eval(path, &Expr::Verbatim(&nested)).is_ok());
+    }
+}
@@ -7,6 +7,7 @@ pub mod expr;
 pub mod extension;
 pub mod func_call;
 pub mod func_like;
+pub(crate) mod map_str;
 pub mod maybe_required;
 pub mod meta;
 pub mod ty;
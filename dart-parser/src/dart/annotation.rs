@@ -1,9 +1,51 @@
-use super::FuncCall;
+use super::{func_call::FuncArg, FuncCall};
 
 /// An annotation must precede a declaration.
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum Annotation<'s> {
     Ident(&'s str),
     /// Type arguments are not allowed in annotations.
     FuncCall(FuncCall<'s>),
 }
+
+impl<'s> Annotation<'s> {
+    /// The name of the constant or of the class, possibly prefixed, e.g.
+    /// `JsonKey` in `@JsonKey(name: 'id')` or `json.JsonKey` in
+    /// `@json.JsonKey()`.
+    ///
+    /// The name of a named constructor is left out, e.g. `Foo` in
+    /// `@Foo.named()`.
+    ///
+    /// The imports are not resolved here, so `@a.B()` is told apart by the
+    /// case of `a`, following the Dart naming conventions: an upper case name
+    /// is a class with a named constructor, a lower case one is an import
+    /// prefix. An upper case prefix (`@M.JsonKey()` gives `M`) or a lower
+    /// case class (`@foo.named()` gives `foo.named`) is then misread.
+    pub fn name(&self) -> &'s str {
+        let name = match self {
+            Annotation::Ident(name) => return name,
+            Annotation::FuncCall(func_call) => func_call.ident.name,
+        };
+
+        match name.rsplit_once('.') {
+            Some((class_name, _))
+                if class_name.contains('.')
+                    || class_name.starts_with(|c: char| c.is_ascii_uppercase()) =>
+            {
+                class_name
+            }
+            _ => name,
+        }
+    }
+
+    pub fn args(&self) -> &[FuncArg<'s>] {
+        match self {
+            Annotation::Ident(_) => &[],
+            Annotation::FuncCall(func_call) => &func_call.args,
+        }
+    }
+
+    pub fn arg(&self, name: &str) -> Option<&FuncArg<'s>> {
+        self.args().iter().find(|arg| arg.name == Some(name))
+    }
+}
@@ -2,14 +2,14 @@ use tiny_set::with_tiny_set;
 
 use super::{
     func_like::{FuncBodyContent, FuncParam, FuncParams},
-    FuncLike, NotFuncType, TypeParam, Var, WithMeta,
+    Comment, FuncLike, NotFuncType, TypeParam, Var, WithMeta,
 };
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct Class<'s> {
     pub modifiers: ClassModifierSet,
     pub name: &'s str,
-    pub type_params: Vec<TypeParam<'s>>,
+    pub type_params: Vec<WithMeta<'s, TypeParam<'s>>>,
     /// The base class.
     pub extends: Option<NotFuncType<'s>>,
     /// Mix-ins.
@@ -19,6 +19,8 @@ pub struct Class<'s> {
     /// Types a mix-in can be added to.
     pub mixin_on: Vec<NotFuncType<'s>>,
     pub body: Vec<WithMeta<'s, ClassMember<'s>>>,
+    /// Comments after the last member.
+    pub dangling_comments: Vec<Comment<'s>>,
 }
 
 /// The possible combinations are:
@@ -57,6 +59,13 @@ pub struct Constructor<'s> {
     pub body: Option<FuncBodyContent<'s>>,
 }
 
+impl<'s> Constructor<'s> {
+    /// The name without the class name, `None` for the unnamed constructor.
+    pub fn short_name(&self) -> Option<&'s str> {
+        self.name.split_once('.').map(|(_, name)| name)
+    }
+}
+
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub enum ConstructorModifier {
     Const,
@@ -1,5 +1,13 @@
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum Comment<'s> {
     SingleLine(&'s str),
     MultiLine(&'s str),
 }
+
+impl<'s> Comment<'s> {
+    pub fn as_str(&self) -> &'s str {
+        match self {
+            Comment::SingleLine(s) | Comment::MultiLine(s) => s,
+        }
+    }
+}
@@ -1,5 +1,7 @@
 #[derive(PartialEq, Eq, Debug)]
 pub enum Directive<'s> {
+    /// A library name, if any (`library;` is allowed).
+    Library(Option<&'s str>),
     Export(Export<'s>),
     Import(Import<'s>),
     Part(&'s str),
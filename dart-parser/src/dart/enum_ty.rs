@@ -1,4 +1,4 @@
-use super::{class::ClassMember, func_call::FuncArg, NotFuncType, WithMeta};
+use super::{class::ClassMember, func_call::FuncArg, Comment, NotFuncType, WithMeta};
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct EnumTy<'s> {
@@ -6,6 +6,8 @@ pub struct EnumTy<'s> {
     pub implements: Vec<NotFuncType<'s>>,
     pub values: Vec<WithMeta<'s, EnumValue<'s>>>,
     pub members: Vec<WithMeta<'s, ClassMember<'s>>>,
+    /// Comments after the last value and after the last member.
+    pub dangling_comments: Vec<Comment<'s>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -1,7 +1,9 @@
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Expr<'s> {
     Verbatim(&'s str),
     Ident(&'s str),
+    /// The body of a single-line non-raw string literal, without the quotes.
+    /// The other string literals are kept verbatim.
     String(&'s str),
 }
 
@@ -1,17 +1,17 @@
-use super::{ty::Type, Annotation, Comment, FuncLike, TypeParam, Var};
+use super::{ty::Type, Comment, FuncLike, TypeParam, Var, WithMeta};
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct Extension<'s> {
     pub name: Option<&'s str>,
-    pub type_params: Vec<TypeParam<'s>>,
+    pub type_params: Vec<WithMeta<'s, TypeParam<'s>>>,
     pub on: Type<'s>,
-    pub body: Vec<ExtensionMember<'s>>,
+    pub body: Vec<WithMeta<'s, ExtensionMember<'s>>>,
+    /// Comments after the last member.
+    pub dangling_comments: Vec<Comment<'s>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
 pub enum ExtensionMember<'s> {
-    Comment(Comment<'s>),
-    Annotation(Annotation<'s>),
     FuncLike(FuncLike<'s>),
     /// Only static fields can be declared in extensions.
     Var(Var<'s>),
@@ -15,7 +15,7 @@ pub struct Func<'s> {
     pub modifiers: FuncModifierSet,
     pub return_type: Type<'s>,
     pub name: &'s str,
-    pub type_params: Vec<TypeParam<'s>>,
+    pub type_params: Vec<WithMeta<'s, TypeParam<'s>>>,
     pub params: FuncParams<'s, FuncParam<'s>>,
     pub body: Option<FuncBody<'s>>,
 }
@@ -24,8 +24,10 @@ pub struct Func<'s> {
 pub struct Operator<'s> {
     pub modifiers: FuncModifierSet,
     pub return_type: Type<'s>,
+    /// The operator as written, e.g. `==`.
+    pub name: &'s str,
     pub operator_type: UserDefOperator,
-    pub type_params: Vec<TypeParam<'s>>,
+    pub type_params: Vec<WithMeta<'s, TypeParam<'s>>>,
     pub params: FuncParams<'s, FuncParam<'s>>,
     pub body: Option<FuncBody<'s>>,
 }
@@ -85,7 +87,7 @@ pub enum FuncModifier {
     Static,
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct FuncParams<'s, ParamPos, ParamNamed = ParamPos> {
     pub positional_req: Vec<WithMeta<'s, ParamPos>>,
     pub extra: Option<FuncParamsExtra<'s, ParamPos, ParamNamed>>,
@@ -100,7 +102,7 @@ impl<'s, T, U> Default for FuncParams<'s, T, U> {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum FuncParamsExtra<'s, ParamPos, ParamNamed = ParamPos> {
     PositionalOpt(Vec<WithMeta<'s, ParamPos>>),
     Named(Vec<WithMeta<'s, MaybeRequired<ParamNamed>>>),
@@ -0,0 +1,464 @@
+use super::{
+    class::{ClassMember, Constructor},
+    directive::{Export, Filter, Import, PartOf},
+    enum_ty::EnumValue,
+    extension::ExtensionMember,
+    func_call::FuncArg,
+    func_like::{
+        Func, FuncBody, FuncBodyContent, FuncParam, FuncParams, FuncParamsExtra, Getter, Operator,
+        Setter,
+    },
+    meta::Meta,
+    ty::{FuncType, FuncTypeParamNamed, FuncTypeParamPos, Tuple, Type},
+    Annotation, Class, Comment, Dart, Directive, EnumTy, Expr, Extension, FuncCall, FuncLike,
+    MaybeRequired, NotFuncType, TypeDef, TypeParam, Var, WithMeta,
+};
+
+/// Copy a node replacing every source slice in it.
+///
+/// Used to move parts of an AST over to another copy of the source.
+pub(crate) trait MapStr<'s>: Sized {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self;
+}
+
+impl<'s> MapStr<'s> for &'s str {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        f(self)
+    }
+}
+
+impl<'s, T: MapStr<'s>> MapStr<'s> for Option<T> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        self.as_ref().map(|value| value.map_str(f))
+    }
+}
+
+impl<'s, T: MapStr<'s>> MapStr<'s> for Vec<T> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        self.iter().map(|value| value.map_str(f)).collect()
+    }
+}
+
+impl<'s, T: MapStr<'s>> MapStr<'s> for Box<T> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        Box::new(self.as_ref().map_str(f))
+    }
+}
+
+impl<'s, T: MapStr<'s>> MapStr<'s> for WithMeta<'s, T> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        WithMeta::new(self.meta.map_str(f), self.as_ref().map_str(f))
+            .with_trailing(self.trailing.map_str(f))
+    }
+}
+
+impl<'s, T: MapStr<'s>> MapStr<'s> for MaybeRequired<T> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        MaybeRequired::new(self.is_required, self.as_ref().map_str(f))
+    }
+}
+
+impl<'s> MapStr<'s> for Meta<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        match self {
+            Meta::Annotation(annotation) => Meta::Annotation(annotation.map_str(f)),
+            Meta::Comment(comment) => Meta::Comment(comment.map_str(f)),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Comment<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        match self {
+            Comment::SingleLine(s) => Comment::SingleLine(f(s)),
+            Comment::MultiLine(s) => Comment::MultiLine(f(s)),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Annotation<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        match self {
+            Annotation::Ident(s) => Annotation::Ident(f(s)),
+            Annotation::FuncCall(func_call) => Annotation::FuncCall(func_call.map_str(f)),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for FuncCall<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        FuncCall {
+            ident: self.ident.map_str(f),
+            args: self.args.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for FuncArg<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        FuncArg {
+            name: self.name.map_str(f),
+            value: self.value.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Expr<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        match self {
+            Expr::Verbatim(s) => Expr::Verbatim(f(s)),
+            Expr::Ident(s) => Expr::Ident(f(s)),
+            Expr::String(s) => Expr::String(f(s)),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Dart<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        match self {
+            Dart::Directive(directive) => Dart::Directive(directive.map_str(f)),
+            Dart::TypeDef(typedef) => Dart::TypeDef(typedef.map_str(f)),
+            Dart::Var(var) => Dart::Var(var.map_str(f)),
+            Dart::FuncLike(func_like) => Dart::FuncLike(func_like.map_str(f)),
+            Dart::Class(class) => Dart::Class(class.map_str(f)),
+            Dart::Enum(enum_ty) => Dart::Enum(enum_ty.map_str(f)),
+            Dart::Extension(extension) => Dart::Extension(extension.map_str(f)),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Directive<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        match self {
+            Directive::Library(name) => Directive::Library(name.map_str(f)),
+            Directive::Export(export) => Directive::Export(export.map_str(f)),
+            Directive::Import(import) => Directive::Import(import.map_str(f)),
+            Directive::Part(s) => Directive::Part(f(s)),
+            Directive::PartOf(part_of) => Directive::PartOf(part_of.map_str(f)),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Export<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        Export {
+            target: f(self.target),
+            filters: self.filters.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Import<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        Import {
+            target: f(self.target),
+            prefix: self.prefix.map_str(f),
+            filters: self.filters.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Filter<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        match self {
+            Filter::Show(names) => Filter::Show(names.map_str(f)),
+            Filter::Hide(names) => Filter::Hide(names.map_str(f)),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for PartOf<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        match self {
+            PartOf::LibPath(s) => PartOf::LibPath(f(s)),
+            PartOf::LibName(s) => PartOf::LibName(f(s)),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for TypeDef<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        TypeDef {
+            name: f(self.name),
+            type_params: self.type_params.map_str(f),
+            target: self.target.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Var<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        Var {
+            modifiers: self.modifiers,
+            var_type: self.var_type.map_str(f),
+            name: f(self.name),
+            initializer: self.initializer.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for FuncLike<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        match self {
+            FuncLike::Func(func) => FuncLike::Func(func.map_str(f)),
+            FuncLike::Operator(operator) => FuncLike::Operator(operator.map_str(f)),
+            FuncLike::Getter(getter) => FuncLike::Getter(getter.map_str(f)),
+            FuncLike::Setter(setter) => FuncLike::Setter(setter.map_str(f)),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Func<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        Func {
+            modifiers: self.modifiers,
+            return_type: self.return_type.map_str(f),
+            name: f(self.name),
+            type_params: self.type_params.map_str(f),
+            params: self.params.map_str(f),
+            body: self.body.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Operator<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        Operator {
+            modifiers: self.modifiers,
+            return_type: self.return_type.map_str(f),
+            name: f(self.name),
+            operator_type: self.operator_type,
+            type_params: self.type_params.map_str(f),
+            params: self.params.map_str(f),
+            body: self.body.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Getter<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        Getter {
+            modifiers: self.modifiers,
+            return_type: self.return_type.map_str(f),
+            name: f(self.name),
+            body: self.body.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Setter<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        Setter {
+            modifiers: self.modifiers,
+            name: f(self.name),
+            params: self.params.map_str(f),
+            body: self.body.map_str(f),
+        }
+    }
+}
+
+impl<'s, T, U> MapStr<'s> for FuncParams<'s, T, U>
+where
+    T: MapStr<'s>,
+    U: MapStr<'s>,
+{
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        FuncParams {
+            positional_req: self.positional_req.map_str(f),
+            extra: self.extra.map_str(f),
+        }
+    }
+}
+
+impl<'s, T, U> MapStr<'s> for FuncParamsExtra<'s, T, U>
+where
+    T: MapStr<'s>,
+    U: MapStr<'s>,
+{
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        match self {
+            FuncParamsExtra::PositionalOpt(params) => {
+                FuncParamsExtra::PositionalOpt(params.map_str(f))
+            }
+            FuncParamsExtra::Named(params) => FuncParamsExtra::Named(params.map_str(f)),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for FuncParam<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        FuncParam {
+            modifiers: self.modifiers,
+            param_type: self.param_type.map_str(f),
+            name: f(self.name),
+            initializer: self.initializer.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for FuncBody<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        FuncBody {
+            modifier: self.modifier,
+            content: self.content.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for FuncBodyContent<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        match self {
+            FuncBodyContent::Block(s) => FuncBodyContent::Block(f(s)),
+            FuncBodyContent::Expr(expr) => FuncBodyContent::Expr(expr.map_str(f)),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Class<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        Class {
+            modifiers: self.modifiers,
+            name: f(self.name),
+            type_params: self.type_params.map_str(f),
+            extends: self.extends.map_str(f),
+            with: self.with.map_str(f),
+            implements: self.implements.map_str(f),
+            mixin_on: self.mixin_on.map_str(f),
+            body: self.body.map_str(f),
+            dangling_comments: self.dangling_comments.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for ClassMember<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        match self {
+            ClassMember::Constructor(constructor) => {
+                ClassMember::Constructor(constructor.map_str(f))
+            }
+            ClassMember::Var(var) => ClassMember::Var(var.map_str(f)),
+            ClassMember::FuncLike(func_like) => ClassMember::FuncLike(func_like.map_str(f)),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Constructor<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        Constructor {
+            modifier: self.modifier,
+            name: f(self.name),
+            params: self.params.map_str(f),
+            body: self.body.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for EnumTy<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        EnumTy {
+            name: f(self.name),
+            implements: self.implements.map_str(f),
+            values: self.values.map_str(f),
+            members: self.members.map_str(f),
+            dangling_comments: self.dangling_comments.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for EnumValue<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        EnumValue {
+            name: f(self.name),
+            args: self.args.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Extension<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        Extension {
+            name: self.name.map_str(f),
+            type_params: self.type_params.map_str(f),
+            on: self.on.map_str(f),
+            body: self.body.map_str(f),
+            dangling_comments: self.dangling_comments.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for ExtensionMember<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        match self {
+            ExtensionMember::FuncLike(func_like) => ExtensionMember::FuncLike(func_like.map_str(f)),
+            ExtensionMember::Var(var) => ExtensionMember::Var(var.map_str(f)),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for TypeParam<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        TypeParam {
+            name: f(self.name),
+            extends: self.extends.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Type<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        match self {
+            Type::NotFunc(ty) => Type::NotFunc(ty.map_str(f)),
+            Type::Func(ty) => Type::Func(ty.map_str(f)),
+            Type::Tuple(ty) => Type::Tuple(ty.map_str(f)),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for NotFuncType<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        NotFuncType {
+            name: f(self.name),
+            type_args: self.type_args.map_str(f),
+            is_nullable: self.is_nullable,
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for FuncType<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        FuncType {
+            return_type: self.return_type.map_str(f),
+            type_params: self.type_params.map_str(f),
+            params: self.params.map_str(f),
+            is_nullable: self.is_nullable,
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for FuncTypeParamPos<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        FuncTypeParamPos {
+            param_type: self.param_type.map_str(f),
+            name: self.name.map_str(f),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for FuncTypeParamNamed<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        FuncTypeParamNamed {
+            param_type: self.param_type.map_str(f),
+            name: f(self.name),
+        }
+    }
+}
+
+impl<'s> MapStr<'s> for Tuple<'s> {
+    fn map_str<F: FnMut(&'s str) -> &'s str>(&self, f: &mut F) -> Self {
+        Tuple {
+            params_pos: self.params_pos.map_str(f),
+            params_named: self.params_named.map_str(f),
+            is_nullable: self.is_nullable,
+        }
+    }
+}
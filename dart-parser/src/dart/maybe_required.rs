@@ -1,6 +1,6 @@
 use std::fmt::Debug;
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct MaybeRequired<T> {
     pub is_required: bool,
     value: T,
@@ -24,6 +24,13 @@ impl<T> MaybeRequired<T> {
             value,
         }
     }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> MaybeRequired<U> {
+        MaybeRequired {
+            is_required: self.is_required,
+            value: f(self.value),
+        }
+    }
 }
 
 impl<T> AsRef<T> for MaybeRequired<T> {
@@ -1,20 +1,46 @@
 use super::{Annotation, Comment};
 
-#[derive(PartialEq, Eq, Debug)]
+/// A node with its comments and annotations.
+///
+/// The comments are attached as follows:
+///
+/// - Leading: the comments (and annotations) preceding a node are its `meta`.
+/// - Trailing: the comments following a node on the same line (e.g.
+///   `int x; // legacy`) are its `trailing` comments, unless they are
+///   followed by more code on that line, which they lead then.
+/// - Dangling: the comments at the end of a body, not followed by any node,
+///   are kept by the body (e.g. `Class::dangling_comments`). Those at the end
+///   of a file are the `trailing` comments of its last item.
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct WithMeta<'s, T> {
     pub meta: Vec<Meta<'s>>,
     value: T,
+    pub trailing: Vec<Comment<'s>>,
 }
 
 impl<'s, T> WithMeta<'s, T> {
     pub fn new(meta: Vec<Meta<'s>>, value: T) -> Self {
-        Self { meta, value }
+        Self {
+            meta,
+            value,
+            trailing: Vec::new(),
+        }
     }
 
     pub fn value(value: T) -> Self {
-        Self {
-            meta: Vec::new(),
-            value,
+        Self::new(Vec::new(), value)
+    }
+
+    pub fn with_trailing(self, trailing: Vec<Comment<'s>>) -> Self {
+        Self { trailing, ..self }
+    }
+
+    /// Map the value, keeping the metadata.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> WithMeta<'s, U> {
+        WithMeta {
+            meta: self.meta,
+            value: f(self.value),
+            trailing: self.trailing,
         }
     }
 }
@@ -25,7 +51,13 @@ impl<'s, T> AsRef<T> for WithMeta<'s, T> {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+impl<'s, T> AsMut<T> for WithMeta<'s, T> {
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum Meta<'s> {
     Annotation(Annotation<'s>),
     Comment(Comment<'s>),
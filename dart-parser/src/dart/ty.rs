@@ -1,6 +1,6 @@
-use super::{func_like::FuncParams, TypeParam};
+use super::{func_like::FuncParams, TypeParam, WithMeta};
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum Type<'s> {
     NotFunc(NotFuncType<'s>),
     Func(Box<FuncType<'s>>),
@@ -13,7 +13,7 @@ impl<'s> Type<'s> {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct NotFuncType<'s> {
     pub name: &'s str,
     pub type_args: Vec<Type<'s>>,
@@ -46,29 +46,29 @@ impl<'s> NotFuncType<'s> {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct FuncType<'s> {
     pub return_type: Type<'s>,
-    pub type_params: Vec<TypeParam<'s>>,
+    pub type_params: Vec<WithMeta<'s, TypeParam<'s>>>,
     pub params: FuncParams<'s, FuncTypeParamPos<'s>, FuncTypeParamNamed<'s>>,
     pub is_nullable: bool,
 }
 
 /// A positional parameter in a function type.
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct FuncTypeParamPos<'s> {
     pub param_type: Type<'s>,
     pub name: Option<&'s str>,
 }
 
 /// A named parameter in a function type.
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct FuncTypeParamNamed<'s> {
     pub param_type: Type<'s>,
     pub name: &'s str,
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Tuple<'s> {
     /// A name can be specified for a positional tuple parameter,
     /// but has no meaning whatsoever.
@@ -1,6 +1,6 @@
 use super::ty::Type;
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct TypeParam<'s> {
     pub name: &'s str,
     pub extends: Option<Type<'s>>,
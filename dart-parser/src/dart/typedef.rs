@@ -1,8 +1,8 @@
-use super::{ty::Type, TypeParam};
+use super::{ty::Type, TypeParam, WithMeta};
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct TypeDef<'s> {
     pub name: &'s str,
-    pub type_params: Vec<TypeParam<'s>>,
+    pub type_params: Vec<WithMeta<'s, TypeParam<'s>>>,
     pub target: Type<'s>,
 }
@@ -10,6 +10,18 @@ pub struct Var<'s> {
     pub initializer: Option<Expr<'s>>,
 }
 
+impl<'s> Var<'s> {
+    /// Whether the variable has an implicit setter: it's neither final nor
+    /// const, or it's `late final` without an initializer.
+    pub fn has_setter(&self) -> bool {
+        let modifiers = self.modifiers;
+
+        !modifiers.contains(VarModifier::Const)
+            && (!modifiers.contains(VarModifier::Final)
+                || modifiers.contains(VarModifier::Late) && self.initializer.is_none())
+    }
+}
+
 #[with_tiny_set]
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 #[repr(usize)]
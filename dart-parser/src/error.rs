@@ -0,0 +1,38 @@
+use std::fmt::Display;
+
+use nom::error::{convert_error, VerboseError};
+
+/// A parse error with its location in the input.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ParseError {
+    /// The byte offset where the innermost parser failed.
+    pub offset: usize,
+    /// A description of the error including the parser context.
+    pub message: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(input: &str, err: nom::Err<VerboseError<&str>>) -> Self {
+        match err {
+            nom::Err::Incomplete(_) => Self {
+                offset: input.len(),
+                message: "Incomplete input".to_owned(),
+            },
+            nom::Err::Error(err) | nom::Err::Failure(err) => Self {
+                offset: err
+                    .errors
+                    .first()
+                    .map_or(0, |(tail, _)| input.len() - tail.len()),
+                message: convert_error(input, err),
+            },
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -0,0 +1,126 @@
+//! Parse individual constructs, e.g. type names taken from annotation
+//! arguments or configuration values.
+//!
+//! The whole input must be consumed, except for the surrounding whitespace
+//! and comments.
+
+use nom::{
+    combinator::{eof, opt},
+    error::VerboseError,
+    sequence::{delimited, pair},
+    Parser,
+};
+
+use crate::{
+    dart::{class::ClassMember, ty::Type, Annotation, Directive, Expr},
+    error::ParseError,
+    parser::{
+        annotation::annotation,
+        class::class_member,
+        common::{spbr, spbrc},
+        directive::directive,
+        expr::expr,
+        meta::with_meta_trailing,
+        ty::ty,
+    },
+    WithMeta,
+};
+
+pub fn parse_type(s: &str) -> Result<Type<'_>, ParseError> {
+    parse_all(s, ty)
+}
+
+pub fn parse_expr(s: &str) -> Result<Expr<'_>, ParseError> {
+    parse_all(s, expr)
+}
+
+pub fn parse_annotation(s: &str) -> Result<Annotation<'_>, ParseError> {
+    parse_all(s, annotation)
+}
+
+/// The leading comments and annotations are collected as metadata,
+/// as well as the trailing comments.
+pub fn parse_class_member(s: &str) -> Result<WithMeta<'_, ClassMember<'_>>, ParseError> {
+    parse_all_with_meta(s, class_member)
+}
+
+/// The metadata is collected like in [`parse_class_member`].
+pub fn parse_directive(s: &str) -> Result<WithMeta<'_, Directive<'_>>, ParseError> {
+    parse_all_with_meta(s, directive)
+}
+
+fn parse_all<'s, P, T>(s: &'s str, p: P) -> Result<T, ParseError>
+where
+    P: Parser<&'s str, T, VerboseError<&'s str>>,
+{
+    delimited(opt(spbrc), p, pair(opt(spbrc), eof))(s)
+        .map(|(_, value)| value)
+        .map_err(|err| ParseError::new(s, err))
+}
+
+fn parse_all_with_meta<'s, P, T>(s: &'s str, p: P) -> Result<WithMeta<'s, T>, ParseError>
+where
+    P: Parser<&'s str, T, VerboseError<&'s str>>,
+{
+    delimited(opt(spbr), with_meta_trailing(p), pair(opt(spbrc), eof))(s)
+        .map(|(_, value)| value)
+        .map_err(|err| ParseError::new(s, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dart::NotFuncType;
+
+    use super::*;
+
+    #[test]
+    fn parse_type_test() {
+        assert_eq!(
+            parse_type(" List<Map<String, int>>? "),
+            Ok(Type::NotFunc(NotFuncType {
+                name: "List",
+                type_args: vec![Type::NotFunc(NotFuncType {
+                    name: "Map",
+                    type_args: vec![
+                        Type::NotFunc(NotFuncType::name("String")),
+                        Type::NotFunc(NotFuncType::name("int")),
+                    ],
+                    is_nullable: false,
+                })],
+                is_nullable: true,
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_type_error_test() {
+        let err = parse_type("List<int> x").unwrap_err();
+
+        assert_eq!(err.offset, 10);
+    }
+
+    #[test]
+    fn parse_class_member_test() {
+        let member = parse_class_member("/// Doc\n@override\nString get id => _id;").unwrap();
+
+        assert_eq!(member.meta.len(), 2);
+        assert!(matches!(member.as_ref(), ClassMember::FuncLike(_)));
+    }
+
+    #[test]
+    fn parse_expr_test() {
+        assert_eq!(parse_expr("'abc'"), Ok(Expr::String("abc")));
+        assert_eq!(parse_expr("r'$abc'"), Ok(Expr::Verbatim("r'$abc'")));
+        assert_eq!(parse_expr("'''abc'''"), Ok(Expr::Verbatim("'''abc'''")));
+        assert!(parse_expr("a, b").is_err());
+    }
+
+    #[test]
+    fn parse_directive_test() {
+        let directive = parse_directive("@deprecated\nimport 'dart:math' as math;").unwrap();
+
+        assert_eq!(directive.meta.len(), 1);
+        assert!(matches!(directive.as_ref(), Directive::Import(_)));
+        assert!(parse_annotation("@JsonKey(name: 'id')").is_ok());
+    }
+}
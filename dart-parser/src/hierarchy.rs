@@ -0,0 +1,484 @@
+use std::path::Path;
+
+use crate::{
+    dart::{
+        class::ClassMember,
+        func_like::{FuncLike, FuncModifier},
+        ty::Type,
+        var::VarModifier,
+        Class, NotFuncType,
+    },
+    type_utils::type_args,
+    Dart, Program, Resolution, Symbol, WithMeta,
+};
+
+/// The supertypes and the inherited members of a class, resolved across
+/// libraries.
+#[derive(Debug)]
+pub struct ClassHierarchy<'a> {
+    program: &'a Program,
+    symbol: Symbol<'a>,
+    class: &'a Class<'a>,
+}
+
+/// A supertype as seen from the class the hierarchy is built for,
+/// i.e. with its type arguments substituted.
+#[derive(Debug)]
+pub struct Supertype<'a> {
+    pub kind: SupertypeKind,
+    pub ty: NotFuncType<'a>,
+    /// The declaration, `None` if the type cannot be resolved, e.g. `Object`
+    /// or a class from a library that isn't loaded.
+    pub class: Option<(Symbol<'a>, &'a Class<'a>)>,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SupertypeKind {
+    /// A superclass (`extends`).
+    Class,
+    /// A mixin (`with`).
+    Mixin,
+    /// An interface (`implements`), or a superclass constraint of a mixin (`on`).
+    Interface,
+}
+
+/// An instance member of a class or of one of its supertypes.
+#[derive(Debug)]
+pub struct Member<'a> {
+    /// The class (or mixin) that declares the member.
+    pub owner: Symbol<'a>,
+    pub member: &'a WithMeta<'a, ClassMember<'a>>,
+    /// The type arguments of the owner, by type parameter name.
+    pub type_args: Vec<(&'a str, Type<'a>)>,
+}
+
+impl<'a> ClassHierarchy<'a> {
+    /// The hierarchy of the class `name` declared in or imported into
+    /// the library at `path`.
+    pub fn new(program: &'a Program, path: &Path, name: &str) -> Result<Self, String> {
+        match program.lookup(path, name)? {
+            Resolution::Declared(symbol) => Self::of(program, symbol),
+            Resolution::Ambiguous(_) => Err(format!("The name {name:?} is ambiguous")),
+            Resolution::Unresolved => Err(format!("Cannot resolve the name {name:?}")),
+        }
+    }
+
+    pub fn of(program: &'a Program, symbol: Symbol<'a>) -> Result<Self, String> {
+        match symbol.declaration.as_ref() {
+            Dart::Class(class) => Ok(Self {
+                program,
+                symbol,
+                class,
+            }),
+            _ => Err(format!(
+                "The declaration in the library at path {:?} is not a class",
+                symbol.library.path()
+            )),
+        }
+    }
+
+    /// All the supertypes, in the order the members are looked up in:
+    /// the mixins (last first) and the superclass, recursively,
+    /// then the interfaces.
+    ///
+    /// Every class appears once, cycles (which are compile-time errors)
+    /// are cut off.
+    pub fn supertypes(&self) -> Vec<Supertype<'a>> {
+        let mut supertypes = Vec::new();
+        let mut visited = vec![self.symbol];
+
+        // The implementation chain first
+        let mut current = Some((self.class, self.symbol, Vec::new()));
+        while let Some((class, symbol, args)) = current.take() {
+            for ty in class.with.iter().rev() {
+                if let Some(supertype) = self.supertype(SupertypeKind::Mixin, ty, symbol, &args) {
+                    push_unique(&mut supertypes, &mut visited, supertype);
+                }
+            }
+            let Some(ty) = &class.extends else {
+                continue;
+            };
+            if let Some(supertype) = self.supertype(SupertypeKind::Class, ty, symbol, &args) {
+                if let Some((symbol, class)) = supertype.class {
+                    if !visited.iter().any(|visited| is_same(visited, &symbol)) {
+                        current = Some((
+                            class,
+                            symbol,
+                            type_args(&class.type_params, &supertype.ty.type_args),
+                        ));
+                    }
+                }
+                push_unique(&mut supertypes, &mut visited, supertype);
+            }
+        }
+
+        // Then the interfaces of the class and of all its supertypes,
+        // including the superclasses and mixins of the interfaces
+        let mut i = 0;
+        let mut pending = vec![(self.class, self.symbol, Vec::new(), false)];
+        loop {
+            let Some((class, symbol, args, is_interface)) = pending.pop() else {
+                let Some(supertype) = supertypes.get(i) else {
+                    break;
+                };
+                i += 1;
+                if let Some((symbol, class)) = supertype.class {
+                    let is_interface = supertype.kind == SupertypeKind::Interface;
+                    pending.push((
+                        class,
+                        symbol,
+                        type_args(&class.type_params, &supertype.ty.type_args),
+                        is_interface,
+                    ));
+                }
+                continue;
+            };
+
+            let inherited = class.extends.iter().chain(&class.with);
+            let interfaces = class.implements.iter().chain(&class.mixin_on);
+            for ty in inherited.filter(|_| is_interface).chain(interfaces) {
+                if let Some(supertype) = self.supertype(SupertypeKind::Interface, ty, symbol, &args)
+                {
+                    push_unique(&mut supertypes, &mut visited, supertype);
+                }
+            }
+        }
+
+        supertypes
+    }
+
+    /// The instance members the class declares or inherits from its
+    /// superclasses and mixins, with the overridden members left out.
+    ///
+    /// A field is overridden by a getter (and by a setter, unless it's final)
+    /// of the same name, and the other way around.
+    pub fn members(&self) -> Vec<Member<'a>> {
+        let supertypes = self.supertypes();
+        let chain = supertypes
+            .iter()
+            .filter(|supertype| supertype.kind != SupertypeKind::Interface);
+
+        self.collect_members(chain)
+    }
+
+    /// The members including the ones of the interfaces, e.g. to list the
+    /// members an abstract class or a mixin requires.
+    pub fn interface_members(&self) -> Vec<Member<'a>> {
+        self.collect_members(self.supertypes().iter())
+    }
+
+    fn collect_members<'b>(
+        &self,
+        supertypes: impl Iterator<Item = &'b Supertype<'a>>,
+    ) -> Vec<Member<'a>>
+    where
+        'a: 'b,
+    {
+        let classes =
+            [(self.symbol, self.class, Vec::new())]
+                .into_iter()
+                .chain(supertypes.filter_map(|supertype| {
+                    let (symbol, class) = supertype.class?;
+                    Some((
+                        symbol,
+                        class,
+                        type_args(&class.type_params, &supertype.ty.type_args),
+                    ))
+                }));
+
+        let mut members = Vec::new();
+        let mut seen = Vec::<String>::new();
+        for (symbol, class, args) in classes {
+            for member in &class.body {
+                let keys = member_keys(member.as_ref());
+                // A member is overridden along with its getter, even if its
+                // setter isn't, e.g. a field by a getter
+                let is_overridden = match keys.iter().find(|key| !key.ends_with('=')) {
+                    Some(getter) => seen.contains(getter),
+                    None => keys.iter().all(|key| seen.contains(key)),
+                };
+                let is_empty = keys.is_empty();
+                for key in keys {
+                    if !seen.contains(&key) {
+                        seen.push(key);
+                    }
+                }
+                if is_empty || is_overridden {
+                    continue;
+                }
+                members.push(Member {
+                    owner: symbol,
+                    member,
+                    type_args: args.clone(),
+                });
+            }
+        }
+
+        members
+    }
+
+    fn supertype(
+        &self,
+        kind: SupertypeKind,
+        ty: &'a NotFuncType<'a>,
+        subtype: Symbol<'a>,
+        args: &[(&'a str, Type<'a>)],
+    ) -> Option<Supertype<'a>> {
+        let class = match self.program.lookup(subtype.library.path(), ty.name).ok()? {
+            Resolution::Declared(symbol) => match symbol.declaration.as_ref() {
+                Dart::Class(class) => Some((symbol, class)),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        Some(Supertype {
+            kind,
+            ty: ty.substitute(args),
+            class,
+        })
+    }
+}
+
+impl<'a> Member<'a> {
+    /// Substitute the type arguments of the owner in a type used
+    /// by the member.
+    pub fn substitute(&self, ty: &Type<'a>) -> Type<'a> {
+        ty.substitute(&self.type_args)
+    }
+
+    /// The type of a field or the return type of a getter, substituted.
+    pub fn value_type(&self) -> Option<Type<'a>> {
+        match self.member.as_ref() {
+            ClassMember::Var(var) => var.var_type.as_ref().map(|ty| self.substitute(ty)),
+            ClassMember::FuncLike(FuncLike::Getter(getter)) => {
+                Some(self.substitute(&getter.return_type))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The names an instance member occupies in the class namespace,
+/// with `=` appended for the setters. Static members and constructors
+/// are not inherited.
+fn member_keys(member: &ClassMember) -> Vec<String> {
+    match member {
+        ClassMember::Constructor(_) => Vec::new(),
+        ClassMember::Var(var) if var.modifiers.contains(VarModifier::Static) => Vec::new(),
+        ClassMember::Var(var) if var.has_setter() => {
+            vec![var.name.to_owned(), format!("{}=", var.name)]
+        }
+        ClassMember::Var(var) => vec![var.name.to_owned()],
+        ClassMember::FuncLike(func_like) => match func_like {
+            FuncLike::Func(func) if !func.modifiers.contains(FuncModifier::Static) => {
+                vec![func.name.to_owned()]
+            }
+            FuncLike::Getter(getter) if !getter.modifiers.contains(FuncModifier::Static) => {
+                vec![getter.name.to_owned()]
+            }
+            FuncLike::Setter(setter) if !setter.modifiers.contains(FuncModifier::Static) => {
+                vec![format!("{}=", setter.name)]
+            }
+            FuncLike::Operator(operator) => vec![format!("operator {:?}", operator.operator_type)],
+            _ => Vec::new(),
+        },
+    }
+}
+
+fn push_unique<'a>(
+    supertypes: &mut Vec<Supertype<'a>>,
+    visited: &mut Vec<Symbol<'a>>,
+    supertype: Supertype<'a>,
+) {
+    match supertype.class {
+        Some((symbol, _)) if visited.iter().any(|visited| is_same(visited, &symbol)) => {}
+        Some((symbol, _)) => {
+            visited.push(symbol);
+            supertypes.push(supertype);
+        }
+        None => {
+            let is_duplicate = supertypes
+                .iter()
+                .any(|other| other.class.is_none() && other.ty.name == supertype.ty.name);
+            if !is_duplicate {
+                supertypes.push(supertype);
+            }
+        }
+    }
+}
+
+fn is_same<'a>(a: &Symbol<'a>, b: &Symbol<'a>) -> bool {
+    std::ptr::eq(a.declaration, b.declaration)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use super::*;
+    use crate::{Package, PackageConfig, ParsedSource};
+
+    const FILES: [(&str, &str); 2] = [
+        (
+            "app/lib/main.dart",
+            "import 'package:models/base.dart';\n\
+             class User<T> extends Entity<T> implements Named {\n  final String name;\n  List<T> tags;\n}\n\
+             class Admin extends User<int> with Timestamps {\n  final String role;\n  DateTime? updatedAt;\n  static int count = 0;\n  Admin(this.role);\n}\n",
+        ),
+        (
+            "models/lib/base.dart",
+            "class Entity<ID> {\n  final ID id;\n  ID? get previousId => null;\n}\n\
+             mixin Timestamps on Entity<int> {\n  DateTime? createdAt;\n  DateTime? updatedAt;\n}\n\
+             abstract class Named {\n  String get name;\n  String get displayName;\n}\n",
+        ),
+    ];
+
+    fn program() -> Program {
+        let files = FILES
+            .iter()
+            .map(|(path, source)| (PathBuf::from(path), source.to_string()))
+            .collect::<HashMap<_, _>>();
+        let config = PackageConfig {
+            packages: vec![Package {
+                name: "models".to_owned(),
+                root: PathBuf::from("models"),
+                lib: PathBuf::from("models/lib"),
+                language_version: None,
+            }],
+        };
+
+        Program::load([PathBuf::from("app/lib/main.dart")], &config, |path| {
+            ParsedSource::parse(files[path].clone()).map_err(|err| err.to_string())
+        })
+        .unwrap()
+    }
+
+    fn member_names(members: &[Member]) -> Vec<String> {
+        members
+            .iter()
+            .map(|member| member_keys(member.member.as_ref())[0].clone())
+            .collect()
+    }
+
+    #[test]
+    fn supertypes_test() {
+        let program = program();
+        let hierarchy =
+            ClassHierarchy::new(&program, Path::new("app/lib/main.dart"), "Admin").unwrap();
+
+        let supertypes = hierarchy
+            .supertypes()
+            .into_iter()
+            .map(|supertype| {
+                let type_args = supertype
+                    .ty
+                    .type_args
+                    .iter()
+                    .map(|ty| match ty {
+                        Type::NotFunc(ty) => ty.name,
+                        _ => "?",
+                    })
+                    .collect::<Vec<_>>();
+                (supertype.kind, supertype.ty.name, type_args)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            supertypes,
+            vec![
+                (SupertypeKind::Mixin, "Timestamps", vec![]),
+                (SupertypeKind::Class, "User", vec!["int"]),
+                (SupertypeKind::Class, "Entity", vec!["int"]),
+                (SupertypeKind::Interface, "Named", vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn members_test() {
+        let program = program();
+        let hierarchy =
+            ClassHierarchy::new(&program, Path::new("app/lib/main.dart"), "Admin").unwrap();
+        let members = hierarchy.members();
+
+        assert_eq!(
+            member_names(&members),
+            vec![
+                "role",
+                "updatedAt",
+                "createdAt",
+                "name",
+                "tags",
+                "id",
+                "previousId"
+            ]
+        );
+        assert_eq!(
+            members[4].value_type(),
+            Some(Type::NotFunc(NotFuncType {
+                name: "List",
+                type_args: vec![Type::NotFunc(NotFuncType::name("int"))],
+                is_nullable: false,
+            }))
+        );
+        assert_eq!(
+            members[4].owner.library.path(),
+            Path::new("app/lib/main.dart")
+        );
+        assert_eq!(
+            members[6].value_type(),
+            Some(Type::NotFunc(NotFuncType {
+                is_nullable: true,
+                ..NotFuncType::name("int")
+            }))
+        );
+        assert_eq!(
+            member_names(&hierarchy.interface_members()),
+            vec![
+                "role",
+                "updatedAt",
+                "createdAt",
+                "name",
+                "tags",
+                "id",
+                "previousId",
+                "displayName"
+            ]
+        );
+    }
+
+    #[test]
+    fn members_override_test() {
+        let program = Program::load([PathBuf::from("a.dart")], &PackageConfig::default(), |_| {
+            ParsedSource::parse(
+                "class A {\n  int x = 0;\n  late final int y;\n  set z(int z) {}\n}\n\
+                 class B extends A {\n  final int x = 1;\n  int get y => 0;\n  int get z => 0;\n}\n"
+                    .to_owned(),
+            )
+            .map_err(|err| err.to_string())
+        })
+        .unwrap();
+        let hierarchy = ClassHierarchy::new(&program, Path::new("a.dart"), "B").unwrap();
+        let members = hierarchy.members();
+
+        assert_eq!(member_names(&members), vec!["x", "y", "z", "z="]);
+        assert!(matches!(
+            members[0].owner.declaration.as_ref(),
+            Dart::Class(class) if class.name == "B"
+        ));
+    }
+
+    #[test]
+    fn hierarchy_cycle_test() {
+        let program = Program::load([PathBuf::from("a.dart")], &PackageConfig::default(), |_| {
+            ParsedSource::parse("class A extends B {}\nclass B extends A {}\n".to_owned())
+                .map_err(|err| err.to_string())
+        })
+        .unwrap();
+        let hierarchy = ClassHierarchy::new(&program, Path::new("a.dart"), "A").unwrap();
+
+        assert_eq!(hierarchy.supertypes().len(), 1);
+        assert!(ClassHierarchy::new(&program, Path::new("a.dart"), "C").is_err());
+    }
+}
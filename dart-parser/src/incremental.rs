@@ -0,0 +1,390 @@
+use std::ops::Range;
+
+use nom::{
+    combinator::{eof, opt},
+    error::Error,
+    Parser,
+};
+
+use crate::{
+    dart::{class::ClassMember, map_str::MapStr, Class},
+    parser::{class::class_members, common::spbr, dart_items, dart_items_to_end},
+    Dart, WithMeta,
+};
+
+/// Replace a byte range of the source with the given text.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range<usize>, text: impl Into<String>) -> Self {
+        Self {
+            range,
+            text: text.into(),
+        }
+    }
+}
+
+/// Validate the edits (all the ranges refer to the original source and must
+/// not overlap) and apply them, returning the edits sorted by position.
+pub(crate) fn apply_edits<'e>(
+    source: &str,
+    edits: &'e [TextEdit],
+) -> Result<(String, Vec<&'e TextEdit>), String> {
+    let mut sorted = Vec::from_iter(edits);
+    sorted.sort_by_key(|edit| edit.range.start);
+
+    let mut result = String::with_capacity(source.len());
+    let mut offset = 0;
+
+    for edit in &sorted {
+        let Range { start, end } = edit.range;
+        if start > end || end > source.len() {
+            return Err(format!("Edit range {start}..{end} is out of bounds"));
+        }
+        if !source.is_char_boundary(start) || !source.is_char_boundary(end) {
+            return Err(format!("Edit range {start}..{end} splits a character"));
+        }
+        if start < offset {
+            return Err(format!("Edit range {start}..{end} overlaps another edit"));
+        }
+
+        result.push_str(&source[offset..start]);
+        result.push_str(&edit.text);
+        offset = end;
+    }
+    result.push_str(&source[offset..]);
+
+    Ok((result, sorted))
+}
+
+/// Reparse the top-level items (and, in a class, the members) affected by the
+/// edits and reuse the rest of the old AST, moving it over to the new source.
+///
+/// The edits must be sorted. Return `None` when the affected part cannot be
+/// reparsed in isolation, which means the whole source has to be reparsed.
+pub(crate) fn reparse<'s>(
+    old_source: &str,
+    old_ast: &[WithMeta<'s, Dart<'s>>],
+    new_source: &'s str,
+    edits: &[&TextEdit],
+) -> Option<Vec<WithMeta<'s, Dart<'s>>>> {
+    if old_ast.is_empty() {
+        return None;
+    }
+
+    let rebase = Rebase::new(old_source, new_source, edits);
+
+    let (mut ast, item_offsets): (Vec<_>, Vec<_>) = old_ast
+        .iter()
+        .map(|item| rebase.node(item))
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+
+    let trailing_ends = Vec::from_iter(old_ast.iter().map(|item| rebase.trailing_end(item)));
+    let bounds = rebase.bounds(0, &item_offsets, &trailing_ends, b";}", old_source.len())?;
+    let Some(affected) = rebase.affected(&bounds) else {
+        return Some(ast);
+    };
+    let affected = rebase.extend(affected, &bounds);
+    let is_last = affected.end == ast.len();
+
+    if affected.len() == 1 {
+        let i = affected.start;
+        if let (Dart::Class(old_class), Dart::Class(class)) = (old_ast[i].as_ref(), ast[i].as_mut())
+        {
+            let item_range = bounds[i]..bounds[i + 1];
+            if let Some((members, range)) = reparse_members(&rebase, old_class, item_range) {
+                class.body.splice(range, members);
+                return Some(ast);
+            }
+        }
+    }
+
+    let range = rebase.new_region(bounds[affected.start]..bounds[affected.end], is_last);
+    // The comments at the end of the file are kept by the last item
+    let items = if is_last {
+        parse_region(dart_items_to_end, &new_source[range])?
+    } else {
+        parse_region(dart_items, &new_source[range])?
+    };
+
+    ast.splice(affected, items);
+
+    Some(ast)
+}
+
+/// Reparse the affected members of a class, if the edits don't touch
+/// the class header or the dangling comments.
+#[allow(clippy::type_complexity)]
+fn reparse_members<'s>(
+    rebase: &Rebase<'_, 's>,
+    class: &Class<'s>,
+    item_range: Range<usize>,
+) -> Option<(Vec<WithMeta<'s, ClassMember<'s>>>, Range<usize>)> {
+    let old_source = rebase.old_source.as_bytes();
+    let body = &class.body;
+
+    // The closing bracket of the class body
+    let body_end = item_range.start
+        + old_source[item_range.clone()]
+            .iter()
+            .rposition(|c| !c.is_ascii_whitespace())?;
+    if old_source[body_end] != b'}' || rebase.touches(body_end + 1..item_range.end) {
+        return None;
+    }
+
+    let member_offsets = body
+        .iter()
+        .map(|member| rebase.node(member).map(|(_, offset)| offset))
+        .collect::<Option<Vec<_>>>()?;
+    let first_offset = *member_offsets.first()?;
+
+    let trailing_ends = Vec::from_iter(body.iter().map(|member| rebase.trailing_end(member)));
+    let mut bounds = rebase.bounds(
+        item_range.start,
+        &member_offsets,
+        &trailing_ends,
+        b";}{",
+        body_end,
+    )?;
+    let header_end = rebase.start(item_range.start, first_offset, None, b";}{")?;
+    bounds[0] = header_end;
+
+    if rebase.is_affected(item_range.start..header_end, false) {
+        return None;
+    }
+    let affected = rebase.extend(rebase.affected(&bounds)?, &bounds);
+    let is_last = affected.end == body.len();
+    if is_last && !class.dangling_comments.is_empty() {
+        return None;
+    }
+
+    let range = rebase.new_region(bounds[affected.start]..bounds[affected.end], is_last);
+    // A comment could move over to the class header, or swallow the closing bracket
+    let (is_open_start, is_open_end) = rebase.open_ends(range.clone());
+    if affected.start == 0 && is_open_start || is_last && is_open_end {
+        return None;
+    }
+    let members = parse_region(class_members, &rebase.new_source[range])?;
+
+    Some((members, affected))
+}
+
+/// Parse a sequence of nodes spanning the whole region.
+///
+/// Dangling comments are not allowed, the region with them has to be
+/// reparsed as a part of the enclosing node.
+fn parse_region<'s, P, T>(mut p: P, s: &'s str) -> Option<Vec<T>>
+where
+    P: Parser<&'s str, Vec<T>, Error<&'s str>>,
+{
+    let (s, _) = opt(spbr::<Error<_>>)(s).ok()?;
+    let (s, nodes) = p.parse(s).ok()?;
+    eof::<_, Error<_>>(s).ok()?;
+
+    Some(nodes)
+}
+
+struct Rebase<'e, 's> {
+    old_source: &'e str,
+    new_source: &'s str,
+    edits: &'e [&'e TextEdit],
+}
+
+impl<'e, 's> Rebase<'e, 's> {
+    fn new(old_source: &'e str, new_source: &'s str, edits: &'e [&'e TextEdit]) -> Self {
+        Self {
+            old_source,
+            new_source,
+            edits,
+        }
+    }
+
+    /// Move a node over to the new source and find the offset of its first
+    /// slice in the old source.
+    ///
+    /// The slices touched by the edits are replaced with empty ones,
+    /// the caller is expected to discard the affected nodes.
+    fn node<T: MapStr<'s>>(&self, node: &T) -> Option<(T, usize)> {
+        let mut first_offset = None;
+
+        let node = node.map_str(&mut |s| match self.old_offset(s) {
+            Some(offset) => {
+                first_offset = Some(first_offset.map_or(offset, |first: usize| first.min(offset)));
+
+                let range = offset..offset + s.len();
+                if self.is_affected(range.clone(), false) {
+                    &self.new_source[..0]
+                } else {
+                    &self.new_source[self.new_range(range)]
+                }
+            }
+            // Not a slice of the source (e.g. an implied `dynamic`)
+            None => s,
+        });
+
+        first_offset.map(|offset| (node, offset))
+    }
+
+    /// The end of the trailing comments of a node in the old source.
+    fn trailing_end<T>(&self, node: &WithMeta<'_, T>) -> Option<usize> {
+        let comment = node.trailing.last()?.as_str();
+
+        Some(self.old_offset(comment)? + comment.len())
+    }
+
+    fn old_offset(&self, s: &str) -> Option<usize> {
+        let offset = (s.as_ptr() as usize).checked_sub(self.old_source.as_ptr() as usize)?;
+
+        (offset + s.len() <= self.old_source.len()).then_some(offset)
+    }
+
+    fn new_range(&self, range: Range<usize>) -> Range<usize> {
+        self.new_offset(range.start)..self.new_offset(range.end)
+    }
+
+    /// The last region also takes the insertions at its end.
+    fn new_region(&self, range: Range<usize>, is_last: bool) -> Range<usize> {
+        let end = if is_last {
+            self.edits
+                .iter()
+                .take_while(|edit| edit.range.start <= range.end)
+                .fold(range.end, |offset, edit| {
+                    offset + edit.text.len() - edit.range.len()
+                })
+        } else {
+            self.new_offset(range.end)
+        };
+
+        self.new_offset(range.start)..end
+    }
+
+    fn new_offset(&self, offset: usize) -> usize {
+        self.edits
+            .iter()
+            .take_while(|edit| edit.range.start < offset)
+            .fold(offset, |offset, edit| {
+                offset + edit.text.len() - edit.range.len()
+            })
+    }
+
+    /// Split a span of the old source into regions, one per node,
+    /// each region starting at the beginning of its node.
+    fn bounds(
+        &self,
+        start: usize,
+        first_offsets: &[usize],
+        trailing_ends: &[Option<usize>],
+        stop_chars: &[u8],
+        end: usize,
+    ) -> Option<Vec<usize>> {
+        let mut bounds = Vec::with_capacity(first_offsets.len() + 1);
+        bounds.push(start);
+        // The trailing comments of the preceding node end its region
+        for (&offset, &prev_end) in first_offsets.iter().skip(1).zip(trailing_ends) {
+            let node_start = self.start(*bounds.last()?, offset, prev_end, stop_chars)?;
+            if node_start <= *bounds.last()? {
+                return None;
+            }
+            bounds.push(node_start);
+        }
+        if end < *bounds.last()? {
+            return None;
+        }
+        bounds.push(end);
+
+        Some(bounds)
+    }
+
+    /// Find the beginning of a node given its first slice, skipping back over
+    /// keywords and modifiers up to the end of the preceding node.
+    ///
+    /// The end of the preceding node is either given (`prev_end`) or found
+    /// as the last of the `stop_chars`.
+    fn start(
+        &self,
+        lower_bound: usize,
+        first_offset: usize,
+        prev_end: Option<usize>,
+        stop_chars: &[u8],
+    ) -> Option<usize> {
+        let prev_end = match prev_end {
+            Some(prev_end) if (lower_bound..=first_offset).contains(&prev_end) => prev_end,
+            Some(_) => return None,
+            None => {
+                let s = &self.old_source.as_bytes()[lower_bound..first_offset];
+                lower_bound + s.iter().rposition(|c| stop_chars.contains(c))? + 1
+            }
+        };
+        let skip = self.old_source.as_bytes()[prev_end..first_offset]
+            .iter()
+            .position(|c| !c.is_ascii_whitespace())
+            .unwrap_or(first_offset - prev_end);
+
+        Some(prev_end + skip)
+    }
+
+    /// Find the range of regions touched by the edits.
+    fn affected(&self, bounds: &[usize]) -> Option<Range<usize>> {
+        let count = bounds.len() - 1;
+        let is_affected = |i: usize| self.is_affected(bounds[i]..bounds[i + 1], i + 1 == count);
+
+        let first = (0..count).find(|&i| is_affected(i))?;
+        let last = (first..count).rev().find(|&i| is_affected(i))?;
+
+        Some(first..last + 1)
+    }
+
+    /// Extend the affected regions over their neighbours while a comment
+    /// on the first or the last line could change sides (e.g. a trailing
+    /// comment becoming the metadata of the next node) or swallow the next node.
+    fn extend(&self, mut affected: Range<usize>, bounds: &[usize]) -> Range<usize> {
+        let count = bounds.len() - 1;
+        loop {
+            let range = self.new_region(bounds[affected.start]..bounds[affected.end], false);
+            let (is_open_start, is_open_end) = self.open_ends(range);
+            if is_open_start && affected.start > 0 {
+                affected.start -= 1;
+            } else if is_open_end && affected.end < count {
+                affected.end += 1;
+            } else {
+                return affected;
+            }
+        }
+    }
+
+    /// Whether the first and the last line of a region of the new source
+    /// may have a comment. Any `/` counts, the strings and the block comments
+    /// left open make the region fail to parse anyway.
+    fn open_ends(&self, range: Range<usize>) -> (bool, bool) {
+        let region = &self.new_source[range];
+        let mut lines = region.split(['\n', '\r']);
+        let first_line = lines.next().unwrap_or_default();
+        let last_line = lines.next_back().unwrap_or(first_line);
+
+        (first_line.contains('/'), last_line.contains('/'))
+    }
+
+    fn touches(&self, range: Range<usize>) -> bool {
+        self.edits
+            .iter()
+            .any(|edit| edit.range.start <= range.end && range.start <= edit.range.end)
+    }
+
+    /// An insertion belongs to the region it's inserted at the beginning of.
+    fn is_affected(&self, range: Range<usize>, is_last: bool) -> bool {
+        self.edits.iter().any(|edit| {
+            let Range { start, end } = edit.range;
+            if start == end {
+                range.start <= start && (start < range.end || is_last && start == range.end)
+            } else {
+                start < range.end && range.start < end
+            }
+        })
+    }
+}
@@ -1,21 +1,183 @@
+mod const_eval;
 mod dart;
+mod error;
+mod fragment;
+mod hierarchy;
+mod incremental;
+mod library;
+mod line_index;
+mod package_config;
+mod parse_iter;
+mod parsed_source;
 mod parser;
+mod prefilter;
+mod property;
+mod pubspec_lock;
+mod query;
+mod symbols;
+mod tokenizer;
+mod type_utils;
+mod validate;
 
+pub use const_eval::{ConstArg, ConstEvaluator, ConstValue};
+pub use dart::class::ClassMember;
+pub use dart::ty::Type;
+pub use dart::Annotation;
 pub use dart::Dart;
+pub use dart::Directive;
+pub use dart::Expr;
 pub use dart::WithMeta;
-use nom::{
-    error::{convert_error, VerboseError},
-    Err,
+pub use error::ParseError;
+pub use fragment::{parse_annotation, parse_class_member, parse_directive, parse_expr, parse_type};
+pub use hierarchy::{ClassHierarchy, Member, Supertype, SupertypeKind};
+pub use incremental::TextEdit;
+pub use library::{Library, Unit};
+pub use line_index::{LineCol, LineIndex};
+use nom::error::VerboseError;
+pub use package_config::{Package, PackageConfig, UriTarget};
+pub use parse_iter::ParseIter;
+pub use parsed_source::ParsedSource;
+pub use prefilter::AnnotationPrefilter;
+pub use property::{Property, PropertyKind};
+pub use pubspec_lock::{pub_cache_dir, LockedPackage, PackageSource, PubspecLock};
+pub use query::{
+    annotations, classes, classes_annotated, enums, enums_implementing, functions,
+    functions_returning, Annotated,
 };
+pub use symbols::{Program, Resolution, Symbol};
+pub use tokenizer::{Token, TokenKind, Tokenizer, Utf8Error};
+pub use type_utils::type_args;
+pub use validate::{validate, Diagnostic};
 
-pub fn parse(s: &str) -> Result<Vec<WithMeta<Dart>>, String> {
-    // Using the simple `nom::error::Error` may be more efficient,
-    // but `nom::error::VerboseError` allows for much better error messages,
-    // which is advantageous for development and debugging.
-    parser::parse::<VerboseError<_>>(s)
-        .map(|(_, value)| value)
-        .map_err(|err| match err {
-            Err::Incomplete(_) => "Incomplete input".to_owned(),
-            Err::Error(err) | Err::Failure(err) => convert_error(s, err),
-        })
+/// How the parse errors are collected.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub enum ErrorMode {
+    /// Parse with the simple `nom::error::Error`, which is cheap,
+    /// and reparse with `nom::error::VerboseError` only on failure
+    /// to produce a detailed error message.
+    #[default]
+    FastFirst,
+    /// Always parse with `nom::error::VerboseError`, which allocates
+    /// on every backtrack.
+    Verbose,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct ParseOptions {
+    pub error_mode: ErrorMode,
+    /// The limit on the nesting of brackets, types and comments,
+    /// beyond which the input is rejected instead of overflowing the stack.
+    pub max_depth: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            error_mode: ErrorMode::default(),
+            max_depth: parser::depth::DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+pub fn parse(s: &str) -> Result<Vec<WithMeta<'_, Dart<'_>>>, ParseError> {
+    parse_with(s, ParseOptions::default())
+}
+
+pub fn parse_with(
+    s: &str,
+    options: ParseOptions,
+) -> Result<Vec<WithMeta<'_, Dart<'_>>>, ParseError> {
+    parser::depth::with_max_depth(options.max_depth, || {
+        if options.error_mode == ErrorMode::FastFirst {
+            if let Ok((_, value)) = parser::parse::<nom::error::Error<_>>(s) {
+                return Ok(value);
+            }
+        }
+
+        parser::parse::<VerboseError<_>>(s)
+            .map(|(_, value)| value)
+            .map_err(|err| ParseError::new(s, err))
+    })
+}
+
+/// Parse the top-level items lazily, e.g. to stop after the directives.
+pub fn parse_iter(s: &str) -> ParseIter<'_> {
+    ParseIter::new(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERBOSE: ParseOptions = ParseOptions {
+        error_mode: ErrorMode::Verbose,
+        max_depth: parser::depth::DEFAULT_MAX_DEPTH,
+    };
+
+    #[test]
+    fn error_mode_test() {
+        let s = "import 'dart:math';\n\nclass A {}\n";
+
+        assert_eq!(parse(s), parse_with(s, VERBOSE));
+    }
+
+    #[test]
+    fn error_mode_error_test() {
+        let s = "import 'dart:math';\n\nclass {}\n";
+
+        let fast_first = parse(s);
+        let verbose = parse_with(s, VERBOSE);
+
+        assert!(fast_first.is_err());
+        assert_eq!(fast_first, verbose);
+        assert_eq!(&s[fast_first.unwrap_err().offset..], "class {}\n");
+    }
+
+    #[test]
+    fn max_depth_test() {
+        let nested =
+            |depth: usize| format!("var x = {}{};\n", "(".repeat(depth), ")".repeat(depth));
+        let options = ParseOptions {
+            max_depth: 16,
+            ..Default::default()
+        };
+
+        assert!(parse_with(&nested(8), options).is_ok());
+        assert!(parse_with(&nested(32), options).is_err());
+    }
+
+    #[test]
+    fn max_depth_default_test() {
+        // The default limit must hold in the default thread stack (2 MiB),
+        // even in a debug build
+        let result = std::thread::Builder::new()
+            .stack_size(2 * 1024 * 1024)
+            .spawn(|| {
+                let sources = [
+                    format!("var x = {};\n", "(".repeat(100_000)),
+                    format!(
+                        "List<{}int{}> x;\n",
+                        "List<".repeat(100_000),
+                        ">".repeat(100_000)
+                    ),
+                    format!("var s = '{}';\n", "${'".repeat(100_000)),
+                    format!("{}\n", "/*".repeat(100_000)),
+                ];
+                // Deep but valid, e.g. a Flutter widget tree
+                let valid = [
+                    format!(
+                        "Widget build() => {}Text(''){};\n",
+                        "Padding(child: ".repeat(100),
+                        ")".repeat(100)
+                    ),
+                    format!("var x = {}1{};\n", "[".repeat(100), "]".repeat(100)),
+                ];
+                sources.iter().all(|s| parse(s).is_err()) && valid.iter().all(|s| parse(s).is_ok())
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+
+        assert!(result);
+    }
 }
@@ -0,0 +1,370 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::{
+    dart::{
+        directive::{Export, Import, PartOf},
+        Directive,
+    },
+    Dart, PackageConfig, ParsedSource, UriTarget, WithMeta,
+};
+
+/// A compilation unit: the main file of a library or one of its parts.
+#[derive(Debug)]
+pub struct Unit {
+    pub path: PathBuf,
+    pub source: ParsedSource,
+}
+
+impl Unit {
+    pub fn new(path: impl Into<PathBuf>, source: ParsedSource) -> Self {
+        Self {
+            path: path.into(),
+            source,
+        }
+    }
+
+    pub(crate) fn directives(&self) -> impl Iterator<Item = &Directive<'_>> {
+        self.source
+            .ast()
+            .iter()
+            .filter_map(|item| match item.as_ref() {
+                Dart::Directive(directive) => Some(directive),
+                _ => None,
+            })
+    }
+}
+
+/// A library, i.e. its main file merged with its parts.
+///
+/// Code generators reason per library, since the generated code is usually
+/// a part of the library it's generated for.
+#[derive(Debug)]
+pub struct Library {
+    /// The main file first, then the parts in the order of the `part` directives.
+    units: Vec<Unit>,
+}
+
+impl Library {
+    /// Load a library given the path to its main file, loading (e.g. reading
+    /// and parsing) every file with `load`.
+    pub fn load<F>(
+        path: impl Into<PathBuf>,
+        config: &PackageConfig,
+        mut load: F,
+    ) -> Result<Self, String>
+    where
+        F: FnMut(&Path) -> Result<ParsedSource, String>,
+    {
+        let path = path.into();
+        let source = load(&path)?;
+
+        Self::with_parts(Unit::new(path, source), config, load)
+    }
+
+    /// Load the parts of a library given its main file.
+    ///
+    /// The part URIs are resolved with `config`, and every part must refer
+    /// back to the main file (or to its library name).
+    pub fn with_parts<F>(main: Unit, config: &PackageConfig, mut load: F) -> Result<Self, String>
+    where
+        F: FnMut(&Path) -> Result<ParsedSource, String>,
+    {
+        if main
+            .directives()
+            .any(|directive| matches!(directive, Directive::PartOf(_)))
+        {
+            return Err(format!(
+                "File at path {:?} is a part of another library",
+                main.path
+            ));
+        }
+
+        let name = library_name(&main);
+        let part_paths = main
+            .directives()
+            .filter_map(|directive| match directive {
+                Directive::Part(uri) => Some(
+                    match config.resolve(uri, &main.path) {
+                        Ok(UriTarget::File(path)) => Ok(path),
+                        Ok(UriTarget::Sdk(_)) => Err(format!("Invalid part URI {uri:?}")),
+                        Err(err) => Err(err),
+                    }
+                    .map_err(|err| {
+                        format!(
+                            "Cannot resolve part URI in file at path {:?}: {err}",
+                            main.path
+                        )
+                    }),
+                ),
+                _ => None,
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut units = Vec::with_capacity(part_paths.len() + 1);
+        for path in part_paths {
+            let source = load(&path)?;
+            let part = Unit::new(path, source);
+            check_part_of(&part, &main.path, name, config)?;
+            units.push(part);
+        }
+        units.insert(0, main);
+
+        Ok(Self { units })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.units[0].path
+    }
+
+    /// The name from the `library` directive, if any.
+    pub fn name(&self) -> Option<&str> {
+        library_name(&self.units[0])
+    }
+
+    /// The main file and the parts.
+    pub fn units(&self) -> &[Unit] {
+        &self.units
+    }
+
+    /// The top-level declarations of all the units, without the directives.
+    pub fn declarations(&self) -> impl Iterator<Item = &WithMeta<'_, Dart<'_>>> {
+        self.units
+            .iter()
+            .flat_map(|unit| unit.source.ast())
+            .filter(|item| !matches!(item.as_ref(), Dart::Directive(_)))
+    }
+
+    pub fn imports(&self) -> impl Iterator<Item = &Import<'_>> {
+        self.directives().filter_map(|directive| match directive {
+            Directive::Import(import) => Some(import),
+            _ => None,
+        })
+    }
+
+    pub fn exports(&self) -> impl Iterator<Item = &Export<'_>> {
+        self.directives().filter_map(|directive| match directive {
+            Directive::Export(export) => Some(export),
+            _ => None,
+        })
+    }
+
+    fn directives(&self) -> impl Iterator<Item = &Directive<'_>> {
+        self.units.iter().flat_map(Unit::directives)
+    }
+}
+
+fn library_name(unit: &Unit) -> Option<&str> {
+    unit.directives().find_map(|directive| match directive {
+        Directive::Library(name) => *name,
+        _ => None,
+    })
+}
+
+fn check_part_of(
+    part: &Unit,
+    main_path: &Path,
+    name: Option<&str>,
+    config: &PackageConfig,
+) -> Result<(), String> {
+    let part_of = part.directives().find_map(|directive| match directive {
+        Directive::PartOf(part_of) => Some(part_of),
+        _ => None,
+    });
+
+    let is_matching = match part_of {
+        Some(PartOf::LibPath(uri)) => matches!(
+            config.resolve(uri, &part.path),
+            Ok(UriTarget::File(path)) if path == normalize(main_path)
+        ),
+        Some(PartOf::LibName(part_of_name)) => name == Some(*part_of_name),
+        None => {
+            return Err(format!(
+                "File at path {:?} is not a part (no `part of` directive)",
+                part.path
+            ))
+        }
+    };
+
+    if is_matching {
+        Ok(())
+    } else {
+        Err(format!(
+            "File at path {:?} is not a part of the library at path {main_path:?}",
+            part.path
+        ))
+    }
+}
+
+/// Resolve a relative URI (e.g. `src/a.dart` or `../a.dart`) against the path
+/// of the file it occurs in.
+///
+/// URIs with a scheme (e.g. `package:` or `dart:`) are not relative.
+pub(crate) fn resolve_relative(file_path: &Path, uri: &str) -> Option<PathBuf> {
+    if uri.contains(':') {
+        return None;
+    }
+
+    let dir = file_path.parent().unwrap_or(Path::new(""));
+
+    Some(normalize(&dir.join(uri)))
+}
+
+/// Remove the `.` and `..` components lexically, without touching
+/// the filesystem.
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                let can_pop = matches!(result.components().next_back(), Some(Component::Normal(_)));
+                if can_pop {
+                    result.pop();
+                } else {
+                    result.push(component);
+                }
+            }
+            _ => result.push(component),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::Package;
+
+    fn loader(
+        files: &[(&str, &str)],
+    ) -> impl FnMut(&Path) -> Result<ParsedSource, String> + 'static {
+        let files = files
+            .iter()
+            .map(|(path, source)| (PathBuf::from(path), source.to_string()))
+            .collect::<HashMap<_, _>>();
+
+        move |path| match files.get(path) {
+            Some(source) => ParsedSource::parse(source.clone()).map_err(|err| err.to_string()),
+            None => Err(format!("No file at path {path:?}")),
+        }
+    }
+
+    #[test]
+    fn library_test() {
+        let library = Library::load(
+            "lib/src/user.dart",
+            &PackageConfig::default(),
+            loader(&[
+                (
+                    "lib/src/user.dart",
+                    "library app.user;\nimport 'dart:core';\npart 'user.g.dart';\npart '../gen/user.x.dart';\nclass User {}\n",
+                ),
+                (
+                    "lib/src/user.g.dart",
+                    "part of 'user.dart';\nMap<String, dynamic> _$UserToJson(User user) => {};\n",
+                ),
+                ("lib/gen/user.x.dart", "part of app.user;\nint x = 0;\n"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(library.path(), Path::new("lib/src/user.dart"));
+        assert_eq!(library.name(), Some("app.user"));
+        assert_eq!(
+            library
+                .units()
+                .iter()
+                .map(|unit| unit.path.as_path())
+                .collect::<Vec<_>>(),
+            vec![
+                Path::new("lib/src/user.dart"),
+                Path::new("lib/src/user.g.dart"),
+                Path::new("lib/gen/user.x.dart"),
+            ]
+        );
+        assert_eq!(library.declarations().count(), 3);
+        assert_eq!(
+            library
+                .imports()
+                .map(|import| import.target)
+                .collect::<Vec<_>>(),
+            vec!["dart:core"]
+        );
+    }
+
+    #[test]
+    fn library_package_uri_test() {
+        let config = PackageConfig {
+            packages: vec![Package {
+                name: "app".to_owned(),
+                root: PathBuf::from("app"),
+                lib: PathBuf::from("app/lib"),
+                language_version: None,
+            }],
+        };
+
+        let library = Library::load(
+            "app/lib/user.dart",
+            &config,
+            loader(&[
+                (
+                    "app/lib/user.dart",
+                    "part 'package:app/src/user.g.dart';
+",
+                ),
+                (
+                    "app/lib/src/user.g.dart",
+                    "part of 'package:app/user.dart';
+",
+                ),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            library.units()[1].path,
+            Path::new("app/lib/src/user.g.dart")
+        );
+    }
+
+    #[test]
+    fn library_part_of_mismatch_test() {
+        let result = Library::load(
+            "lib/a.dart",
+            &PackageConfig::default(),
+            loader(&[
+                ("lib/a.dart", "part 'a.g.dart';\n"),
+                ("lib/a.g.dart", "part of 'b.dart';\n"),
+            ]),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn library_of_part_test() {
+        let result = Library::load(
+            "lib/a.g.dart",
+            &PackageConfig::default(),
+            loader(&[("lib/a.g.dart", "part of 'a.dart';\n")]),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn normalize_test() {
+        assert_eq!(
+            normalize(Path::new("lib/src/../gen/./a.dart")),
+            PathBuf::from("lib/gen/a.dart")
+        );
+        assert_eq!(
+            normalize(Path::new("../a.dart")),
+            PathBuf::from("../a.dart")
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use std::ops::Range;
+
+/// Convert between byte offsets and line/column positions in a source.
+///
+/// Lines are terminated by `\n`, `\r\n` or a lone `\r`. Lines and columns are
+/// zero-based, columns are counted either in bytes (UTF-8 code units)
+/// or in UTF-16 code units (as used by the Dart analyzer and LSP).
+#[derive(Debug)]
+pub struct LineIndex<'s> {
+    source: &'s str,
+    /// The offset of the first character of every line.
+    line_starts: Vec<usize>,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub struct LineCol {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl<'s> LineIndex<'s> {
+    pub fn new(source: &'s str) -> Self {
+        let bytes = source.as_bytes();
+        let mut line_starts = vec![0];
+
+        for (i, &c) in bytes.iter().enumerate() {
+            let is_break = c == b'\n' || c == b'\r' && bytes.get(i + 1) != Some(&b'\n');
+            if is_break {
+                line_starts.push(i + 1);
+            }
+        }
+
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// The content of a line without the line break.
+    pub fn line(&self, line: usize) -> Option<&'s str> {
+        let range = self.line_range(line)?;
+
+        Some(&self.source[range])
+    }
+
+    /// The position of a byte offset with the column in bytes.
+    pub fn line_col(&self, offset: usize) -> Option<LineCol> {
+        if !self.source.is_char_boundary(offset) {
+            return None;
+        }
+
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+
+        Some(LineCol {
+            line,
+            col: offset - self.line_starts[line],
+        })
+    }
+
+    /// The position of a byte offset with the column in UTF-16 code units.
+    pub fn line_col_utf16(&self, offset: usize) -> Option<LineCol> {
+        let LineCol { line, col } = self.line_col(offset)?;
+        let start = self.line_starts[line];
+
+        Some(LineCol {
+            line,
+            col: utf16_len(&self.source[start..start + col]),
+        })
+    }
+
+    /// The byte offset of a position with the column in bytes.
+    pub fn offset(&self, pos: LineCol) -> Option<usize> {
+        let range = self.line_range(pos.line)?;
+        let offset = range.start + pos.col;
+
+        (offset <= range.end && self.source.is_char_boundary(offset)).then_some(offset)
+    }
+
+    /// The byte offset of a position with the column in UTF-16 code units.
+    pub fn offset_utf16(&self, pos: LineCol) -> Option<usize> {
+        let range = self.line_range(pos.line)?;
+        let line = &self.source[range.clone()];
+
+        let mut col = 0;
+        for (i, c) in line.char_indices() {
+            if col >= pos.col {
+                // A column in the middle of a surrogate pair is not valid
+                return (col == pos.col).then_some(range.start + i);
+            }
+            col += c.len_utf16();
+        }
+
+        (col == pos.col).then_some(range.end)
+    }
+
+    /// The byte range of a slice of the source.
+    pub fn range(&self, slice: &str) -> Option<Range<usize>> {
+        let start = (slice.as_ptr() as usize).checked_sub(self.source.as_ptr() as usize)?;
+        let end = start + slice.len();
+
+        (end <= self.source.len()).then_some(start..end)
+    }
+
+    /// The positions of the start and the end of a slice of the source,
+    /// with the columns in bytes.
+    pub fn slice_line_cols(&self, slice: &str) -> Option<Range<LineCol>> {
+        let range = self.range(slice)?;
+
+        Some(self.line_col(range.start)?..self.line_col(range.end)?)
+    }
+
+    fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.line_starts.get(line)?;
+        let end = match self.line_starts.get(line + 1) {
+            Some(&next) => {
+                let content = &self.source.as_bytes()[..next];
+                if content.ends_with(b"\r\n") {
+                    next - 2
+                } else {
+                    next - 1
+                }
+            }
+            None => self.source.len(),
+        };
+
+        Some(start..end)
+    }
+}
+
+fn utf16_len(s: &str) -> usize {
+    if s.is_ascii() {
+        s.len()
+    } else {
+        s.chars().map(char::len_utf16).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_index_test() {
+        let index = LineIndex::new("a\nbc\r\nd\re");
+
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line(1), Some("bc"));
+        assert_eq!(index.line(2), Some("d"));
+        assert_eq!(index.line_col(3), Some(LineCol { line: 1, col: 1 }));
+        assert_eq!(index.line_col(6), Some(LineCol { line: 2, col: 0 }));
+        assert_eq!(index.line_col(9), Some(LineCol { line: 3, col: 1 }));
+        assert_eq!(index.offset(LineCol { line: 1, col: 2 }), Some(4));
+        assert_eq!(index.offset(LineCol { line: 1, col: 3 }), None);
+        assert_eq!(index.offset(LineCol { line: 4, col: 0 }), None);
+    }
+
+    #[test]
+    fn line_index_utf16_test() {
+        let source = "var s = '😀é';\nx";
+        let index = LineIndex::new(source);
+        let e = source.find('é').unwrap();
+
+        assert_eq!(index.line_col(e), Some(LineCol { line: 0, col: 13 }));
+        assert_eq!(index.line_col_utf16(e), Some(LineCol { line: 0, col: 11 }));
+        assert_eq!(index.offset_utf16(LineCol { line: 0, col: 11 }), Some(e));
+        assert_eq!(index.offset_utf16(LineCol { line: 0, col: 10 }), None);
+        assert_eq!(index.line_col(e + 1), None);
+    }
+
+    #[test]
+    fn line_index_slice_test() {
+        let source = "class A {\n  int x;\n}\n";
+        let index = LineIndex::new(source);
+        let slice = &source[12..18];
+
+        assert_eq!(
+            index.slice_line_cols(slice),
+            Some(LineCol { line: 1, col: 2 }..LineCol { line: 1, col: 8 })
+        );
+        assert_eq!(index.range("int x;"), None);
+    }
+}
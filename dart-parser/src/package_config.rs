@@ -0,0 +1,350 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::library::{normalize, resolve_relative};
+
+/// The packages a Dart package depends on, as resolved by `pub get`.
+///
+/// Read from `.dart_tool/package_config.json`, or from the legacy `.packages`
+/// file when there is no package config.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct PackageConfig {
+    pub packages: Vec<Package>,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Package {
+    pub name: String,
+    /// The root directory of the package, i.e. the one with `pubspec.yaml`.
+    pub root: PathBuf,
+    /// The directory the `package:<name>/` URIs point to, usually `<root>/lib`.
+    pub lib: PathBuf,
+    pub language_version: Option<String>,
+}
+
+/// The target of an import, export or part URI.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum UriTarget<'a> {
+    File(PathBuf),
+    /// A library of the SDK, e.g. `async` for `dart:async`.
+    Sdk(&'a str),
+}
+
+impl PackageConfig {
+    /// Load the package config of the package at `pkg_dir`, reading the files
+    /// with `read`.
+    ///
+    /// Return `None` if neither `.dart_tool/package_config.json`
+    /// nor `.packages` exists, e.g. if `pub get` hasn't been run.
+    pub fn load<F>(pkg_dir: &Path, mut read: F) -> Result<Option<Self>, String>
+    where
+        F: FnMut(&Path) -> io::Result<String>,
+    {
+        let mut read = |path: PathBuf| match read(&path) {
+            Ok(s) => Ok(Some((s, path))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Cannot read file at path {path:?}: {e}")),
+        };
+
+        if let Some((json, path)) = read(pkg_dir.join(".dart_tool/package_config.json"))? {
+            return Self::from_json(&json, &path).map(Some);
+        }
+        if let Some((packages, path)) = read(pkg_dir.join(".packages"))? {
+            return Self::from_packages(&packages, &path).map(Some);
+        }
+
+        Ok(None)
+    }
+
+    /// Parse a `package_config.json` file, resolving the relative root URIs
+    /// against the directory of `path`.
+    pub fn from_json(json: &str, path: &Path) -> Result<Self, String> {
+        let invalid = |msg: &str| format!("Invalid package config at path {path:?}: {msg}");
+
+        let config: Value = serde_json::from_str(json).map_err(|e| invalid(&e.to_string()))?;
+        if config["configVersion"].as_u64() != Some(2) {
+            return Err(invalid("unsupported `configVersion`, expected 2"));
+        }
+        let entries = config["packages"]
+            .as_array()
+            .ok_or_else(|| invalid("`packages` is not an array"))?;
+
+        let packages = entries
+            .iter()
+            .map(|entry| {
+                let field = |key: &str| {
+                    entry[key]
+                        .as_str()
+                        .ok_or_else(|| invalid(&format!("missing `{key}` in a package")))
+                };
+
+                let name = field("name")?;
+                let root_uri = field("rootUri")?;
+                let root = resolve_file_uri(path, &with_trailing_slash(root_uri))
+                    .ok_or_else(|| invalid(&format!("unsupported root URI {root_uri:?}")))?;
+                let lib = match entry.get("packageUri") {
+                    Some(_) => {
+                        let package_uri = field("packageUri")?;
+                        resolve_file_uri(&root.join("pubspec.yaml"), package_uri).ok_or_else(
+                            || invalid(&format!("unsupported package URI {package_uri:?}")),
+                        )?
+                    }
+                    None => root.clone(),
+                };
+
+                Ok(Package {
+                    name: name.to_owned(),
+                    root,
+                    lib,
+                    language_version: entry["languageVersion"].as_str().map(ToOwned::to_owned),
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self { packages })
+    }
+
+    /// Parse a legacy `.packages` file, i.e. `<name>:<lib URI>` lines.
+    ///
+    /// The root of a package is assumed to be the parent of its `lib` directory.
+    pub fn from_packages(packages: &str, path: &Path) -> Result<Self, String> {
+        let packages = packages
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (name, uri) = line.split_once(':').ok_or_else(|| {
+                    format!("Invalid line {line:?} in the packages file at path {path:?}")
+                })?;
+                let lib = resolve_file_uri(path, &with_trailing_slash(uri)).ok_or_else(|| {
+                    format!("Unsupported URI {uri:?} in the packages file at path {path:?}")
+                })?;
+
+                Ok(Package {
+                    name: name.to_owned(),
+                    root: lib.parent().map_or_else(|| lib.clone(), Path::to_path_buf),
+                    lib,
+                    language_version: None,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self { packages })
+    }
+
+    pub fn package(&self, name: &str) -> Option<&Package> {
+        self.packages.iter().find(|package| package.name == name)
+    }
+
+    /// The package that contains the file at `path`, i.e. the one with
+    /// the longest matching root.
+    pub fn package_of(&self, path: &Path) -> Option<&Package> {
+        let path = normalize(path);
+
+        self.packages
+            .iter()
+            .filter(|package| path.starts_with(&package.root))
+            .max_by_key(|package| package.root.components().count())
+    }
+
+    /// Resolve the URI of an import, export or part in the file at `file_path`.
+    ///
+    /// `package:` URIs are mapped to the `lib` directory of the package,
+    /// relative URIs are resolved against the directory of the file,
+    /// and `dart:` URIs are SDK libraries, which have no path.
+    pub fn resolve<'a>(&self, uri: &'a str, file_path: &Path) -> Result<UriTarget<'a>, String> {
+        if let Some(library) = uri.strip_prefix("dart:") {
+            return Ok(UriTarget::Sdk(library));
+        }
+
+        if let Some(package_path) = uri.strip_prefix("package:") {
+            let (name, rel_path) = package_path
+                .split_once('/')
+                .filter(|(name, rel_path)| !name.is_empty() && !rel_path.is_empty())
+                .ok_or_else(|| format!("Invalid package URI {uri:?}"))?;
+            let package = self
+                .package(name)
+                .ok_or_else(|| format!("Unknown package {name:?} in URI {uri:?}"))?;
+
+            return Ok(UriTarget::File(normalize(
+                &package.lib.join(percent_decode(rel_path)?),
+            )));
+        }
+
+        resolve_file_uri(file_path, uri)
+            .map(UriTarget::File)
+            .ok_or_else(|| format!("Unsupported URI {uri:?}"))
+    }
+}
+
+/// Resolve a relative or `file:` URI against the path of the file it occurs in.
+fn resolve_file_uri(file_path: &Path, uri: &str) -> Option<PathBuf> {
+    match uri.strip_prefix("file://") {
+        Some(path) => Some(normalize(Path::new(&percent_decode(path).ok()?))),
+        None => resolve_relative(file_path, &percent_decode(uri).ok()?),
+    }
+}
+
+/// The root URIs point to directories, with or without the trailing slash.
+fn with_trailing_slash(uri: &str) -> String {
+    if uri.ends_with('/') {
+        uri.to_owned()
+    } else {
+        format!("{uri}/")
+    }
+}
+
+fn percent_decode(s: &str) -> Result<String, String> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = s
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| format!("Invalid percent-encoding in URI {s:?}"))?;
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).map_err(|_| format!("Invalid UTF-8 in URI {s:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACKAGE_CONFIG: &str = r#"{
+  "configVersion": 2,
+  "packages": [
+    {
+      "name": "collection",
+      "rootUri": "file:///home/user/.pub-cache/hosted/pub.dev/collection-1.18.0",
+      "packageUri": "lib/",
+      "languageVersion": "2.18"
+    },
+    {
+      "name": "app",
+      "rootUri": "../",
+      "packageUri": "lib/",
+      "languageVersion": "3.0"
+    },
+    {
+      "name": "app_test",
+      "rootUri": "../test%20utils"
+    }
+  ],
+  "generator": "pub"
+}"#;
+
+    fn config() -> PackageConfig {
+        PackageConfig::from_json(
+            PACKAGE_CONFIG,
+            Path::new("/work/app/.dart_tool/package_config.json"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn package_config_test() {
+        let config = config();
+
+        assert_eq!(
+            config.package("collection"),
+            Some(&Package {
+                name: "collection".to_owned(),
+                root: PathBuf::from("/home/user/.pub-cache/hosted/pub.dev/collection-1.18.0"),
+                lib: PathBuf::from("/home/user/.pub-cache/hosted/pub.dev/collection-1.18.0/lib"),
+                language_version: Some("2.18".to_owned()),
+            })
+        );
+        assert_eq!(
+            config.package("app").map(|package| package.lib.as_path()),
+            Some(Path::new("/work/app/lib"))
+        );
+        assert_eq!(
+            config
+                .package("app_test")
+                .map(|package| package.lib.as_path()),
+            Some(Path::new("/work/app/test utils"))
+        );
+        assert_eq!(
+            config
+                .package_of(Path::new("/work/app/lib/src/a.dart"))
+                .map(|package| package.name.as_str()),
+            Some("app")
+        );
+    }
+
+    #[test]
+    fn package_config_resolve_test() {
+        let config = config();
+        let file_path = Path::new("/work/app/lib/src/a.dart");
+
+        assert_eq!(
+            config.resolve("package:collection/src/utils.dart", file_path),
+            Ok(UriTarget::File(PathBuf::from(
+                "/home/user/.pub-cache/hosted/pub.dev/collection-1.18.0/lib/src/utils.dart"
+            )))
+        );
+        assert_eq!(
+            config.resolve("../b.dart", file_path),
+            Ok(UriTarget::File(PathBuf::from("/work/app/lib/b.dart")))
+        );
+        assert_eq!(
+            config.resolve("dart:async", file_path),
+            Ok(UriTarget::Sdk("async"))
+        );
+        assert!(config.resolve("package:unknown/a.dart", file_path).is_err());
+        assert!(config.resolve("package:collection", file_path).is_err());
+        assert!(config
+            .resolve("https://example.com/a.dart", file_path)
+            .is_err());
+    }
+
+    #[test]
+    fn packages_file_test() {
+        let config = PackageConfig::from_packages(
+            "# Generated by pub\ncollection:file:///pub-cache/collection-1.18.0/lib/\napp:lib/\n",
+            Path::new("/work/app/.packages"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.resolve("package:app/a.dart", Path::new("/work/app/bin/main.dart")),
+            Ok(UriTarget::File(PathBuf::from("/work/app/lib/a.dart")))
+        );
+        assert_eq!(
+            config
+                .package("collection")
+                .map(|package| package.root.as_path()),
+            Some(Path::new("/pub-cache/collection-1.18.0"))
+        );
+    }
+
+    #[test]
+    fn package_config_load_test() {
+        let read = |path: &Path| match path.to_str() {
+            Some("app/.packages") => Ok("app:lib/\n".to_owned()),
+            _ => Err(io::Error::from(io::ErrorKind::NotFound)),
+        };
+
+        let config = PackageConfig::load(Path::new("app"), read).unwrap();
+
+        assert_eq!(
+            config.and_then(|config| config.package("app").map(|package| package.lib.clone())),
+            Some(PathBuf::from("app/lib"))
+        );
+        assert_eq!(PackageConfig::load(Path::new("other"), read), Ok(None));
+    }
+}
@@ -0,0 +1,99 @@
+use nom::{combinator::opt, error::VerboseError};
+
+use crate::{
+    parser::{common::spbr, dart_item_or_end},
+    Dart, ParseError, WithMeta,
+};
+
+/// Parse the top-level items one at a time.
+///
+/// Parsing stops at the first error, which is yielded as the last item.
+pub struct ParseIter<'s> {
+    source: &'s str,
+    /// `None` once the input is exhausted or an error is encountered.
+    tail: Option<&'s str>,
+}
+
+impl<'s> ParseIter<'s> {
+    pub fn new(source: &'s str) -> Self {
+        let tail = opt(spbr::<nom::error::Error<_>>)(source)
+            .map(|(tail, _)| tail)
+            .unwrap_or(source);
+
+        Self {
+            source,
+            tail: Some(tail),
+        }
+    }
+}
+
+impl<'s> Iterator for ParseIter<'s> {
+    type Item = Result<WithMeta<'s, Dart<'s>>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let tail = self.tail?;
+        // Only pay for the verbose error when the item fails to parse
+        let result = dart_item_or_end::<nom::error::Error<_>>(tail)
+            .or_else(|_| dart_item_or_end::<VerboseError<_>>(tail));
+
+        match result {
+            Ok((tail, Some(item))) => {
+                self.tail = Some(tail);
+                Some(Ok(item))
+            }
+            Ok((_, None)) => {
+                self.tail = None;
+                None
+            }
+            Err(err) => {
+                self.tail = None;
+                Some(Err(ParseError::new(self.source, err)))
+            }
+        }
+    }
+}
+
+impl<'s> std::iter::FusedIterator for ParseIter<'s> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::dart::Directive;
+
+    use super::*;
+
+    #[test]
+    fn parse_iter_test() {
+        let source = "// Header\nimport 'a.dart';\n\nclass A {}\n// Trailer\n";
+
+        assert_eq!(
+            ParseIter::new(source).collect::<Result<Vec<_>, _>>(),
+            crate::parse(source)
+        );
+    }
+
+    #[test]
+    fn parse_iter_lazy_test() {
+        // The broken class past the directives is never reached
+        let source = "import 'a.dart';\nexport 'b.dart';\nclass {";
+
+        let directives = ParseIter::new(source)
+            .map_while(|item| match item.ok()?.as_ref() {
+                Dart::Directive(Directive::Import(import)) => Some(import.target),
+                Dart::Directive(Directive::Export(export)) => Some(export.target),
+                _ => None,
+            })
+            .take(2)
+            .collect::<Vec<_>>();
+
+        assert_eq!(directives, vec!["a.dart", "b.dart"]);
+    }
+
+    #[test]
+    fn parse_iter_error_test() {
+        let mut items = ParseIter::new("import 'a.dart';\nclass {");
+
+        assert!(items.next().is_some_and(|item| item.is_ok()));
+        assert!(items.next().is_some_and(|item| item.is_err()));
+        assert!(items.next().is_none());
+    }
+}
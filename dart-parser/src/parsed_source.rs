@@ -0,0 +1,240 @@
+use crate::{incremental, Dart, ParseError, TextEdit, WithMeta};
+
+/// An owned source paired with its AST.
+///
+/// The AST borrows from the source, which lives on the heap and is never
+/// mutated or exposed mutably, so moving the container doesn't invalidate the
+/// AST. The AST is only handed out with a lifetime bound to the container.
+#[derive(Debug)]
+pub struct ParsedSource {
+    // Declared before `source` to be dropped first.
+    ast: Vec<WithMeta<'static, Dart<'static>>>,
+    source: String,
+}
+
+impl ParsedSource {
+    pub fn parse(source: String) -> Result<Self, ParseError> {
+        Self::try_new(source, crate::parse)
+    }
+
+    /// Build the AST with a custom parsing function.
+    pub fn try_new<F, E>(source: String, f: F) -> Result<Self, E>
+    where
+        F: for<'s> FnOnce(&'s str) -> Result<Vec<WithMeta<'s, Dart<'s>>>, E>,
+    {
+        // SAFETY: the string buffer outlives the AST (see the field order) and
+        // is never mutated, and `Self::ast()` shortens the lifetime back.
+        let ast = f(unsafe { erase_lifetime(source.as_str()) })?;
+
+        Ok(Self { ast, source })
+    }
+
+    /// Apply the edits to the source and reparse only the affected parts of it.
+    ///
+    /// The edit ranges refer to the current source and must not overlap.
+    pub fn reparse(&self, edits: &[TextEdit]) -> Result<Self, String> {
+        let (source, edits) = incremental::apply_edits(&self.source, edits)?;
+
+        // SAFETY: see `Self::try_new()`.
+        let new_source = unsafe { erase_lifetime(source.as_str()) };
+        match incremental::reparse(&self.source, &self.ast, new_source, &edits) {
+            Some(ast) => Ok(Self { ast, source }),
+            None => Self::parse(source).map_err(|err| err.to_string()),
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn ast(&self) -> &[WithMeta<'_, Dart<'_>>] {
+        &self.ast
+    }
+
+    pub fn into_source(self) -> String {
+        self.source
+    }
+}
+
+unsafe fn erase_lifetime<'a>(value: &str) -> &'a str {
+    &*(value as *const str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsed_source_move_test() {
+        let parsed = ParsedSource::parse("import 'dart:math';\n".to_owned()).unwrap();
+
+        let parsed = std::thread::spawn(move || parsed).join().unwrap();
+
+        assert_eq!(parsed.ast().len(), 1);
+        assert_eq!(parsed.source(), "import 'dart:math';\n");
+    }
+
+    fn assert_reparse(source: &str, edits: &[TextEdit]) {
+        let parsed = ParsedSource::parse(source.to_owned()).unwrap();
+        let reparsed = parsed.reparse(edits).unwrap();
+        let expected = ParsedSource::parse(reparsed.source().to_owned()).unwrap();
+
+        assert_eq!(reparsed.ast(), expected.ast());
+    }
+
+    const DART_REPARSE: &str = r#"import 'dart:math';
+
+// A comment
+final String name = "name";
+
+class Record {
+  @override
+  final String id;
+
+  String get name => id;
+}
+
+void f() {}
+"#;
+
+    #[test]
+    fn reparse_item_test() {
+        assert_reparse(DART_REPARSE, &[TextEdit::new(27..32, "const")]);
+    }
+
+    #[test]
+    fn reparse_insert_item_test() {
+        assert_reparse(DART_REPARSE, &[TextEdit::new(21..21, "int x;\n")]);
+    }
+
+    #[test]
+    fn reparse_member_test() {
+        let edit_at = DART_REPARSE.find("id;").unwrap();
+
+        assert_reparse(DART_REPARSE, &[TextEdit::new(edit_at..edit_at + 2, "key")]);
+    }
+
+    #[test]
+    fn reparse_member_modifier_test() {
+        let edit_at = DART_REPARSE.find("final String id").unwrap();
+
+        assert_reparse(DART_REPARSE, &[TextEdit::new(edit_at..edit_at + 6, "")]);
+    }
+
+    #[test]
+    fn reparse_class_header_test() {
+        let edit_at = DART_REPARSE.find(" {\n  @override").unwrap();
+
+        assert_reparse(DART_REPARSE, &[TextEdit::new(edit_at..edit_at, "<T>")]);
+    }
+
+    #[test]
+    fn reparse_end_test() {
+        let len = DART_REPARSE.len();
+
+        assert_reparse(DART_REPARSE, &[TextEdit::new(len..len, "int y = 0;\n")]);
+    }
+
+    const DART_REPARSE_COMMENTS: &str = r#"final a = 0; // See b; and c}
+
+class Record {
+  final String id; // Unique; see {key}
+  final String key;
+  // Dangling
+}
+
+void f() {}
+// The end
+"#;
+
+    #[test]
+    fn reparse_trailing_comment_test() {
+        let edit_at = DART_REPARSE_COMMENTS.find("id;").unwrap();
+
+        assert_reparse(
+            DART_REPARSE_COMMENTS,
+            &[TextEdit::new(edit_at..edit_at + 2, "uid")],
+        );
+
+        let edit_at = DART_REPARSE_COMMENTS.find("final a").unwrap();
+
+        assert_reparse(
+            DART_REPARSE_COMMENTS,
+            &[TextEdit::new(edit_at..edit_at + 5, "const")],
+        );
+    }
+
+    #[test]
+    fn reparse_dangling_comment_test() {
+        let edit_at = DART_REPARSE_COMMENTS.find("key;").unwrap();
+
+        assert_reparse(
+            DART_REPARSE_COMMENTS,
+            &[TextEdit::new(edit_at..edit_at + 3, "name")],
+        );
+
+        let edit_at = DART_REPARSE_COMMENTS.find("// The end").unwrap();
+
+        assert_reparse(
+            DART_REPARSE_COMMENTS,
+            &[TextEdit::new(edit_at..edit_at + 10, "")],
+        );
+    }
+
+    const DART_REPARSE_FUZZ: &str = r#"import 'dart:math'; // math
+/// A record.
+class Record<T> extends Base {
+  final int id; // trailing
+  String name = "a // b";
+  /* block */ int get size => 1;
+  Record(this.id);
+}
+enum Color { red, green; final int x = 0; }
+extension on int { int get twice => this * 2; } // end
+var s = 'it''s'; /* c */ final t = r"\d";
+"#;
+
+    #[test]
+    fn reparse_differential_test() {
+        const SNIPPETS: &[&str] = &[
+            "", "", " ", "\n", "//", "/*", "*/", "'", "\"", ";", "{", "}", "x", "int a;", "@A ",
+            "/// ", "\r",
+        ];
+        let parsed = ParsedSource::parse(DART_REPARSE_FUZZ.to_owned()).unwrap();
+
+        // xorshift, deterministic
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = |bound: usize| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % bound as u64) as usize
+        };
+
+        for _ in 0..3_000 {
+            let start = next(DART_REPARSE_FUZZ.len() + 1);
+            let end = (start + next(4)).min(DART_REPARSE_FUZZ.len());
+            let text = SNIPPETS[next(SNIPPETS.len())];
+            let edits = [TextEdit::new(start..end, text)];
+
+            let reparsed = parsed.reparse(&edits);
+            let (new_source, _) = incremental::apply_edits(DART_REPARSE_FUZZ, &edits).unwrap();
+            let expected = crate::parse(&new_source);
+
+            match (&reparsed, &expected) {
+                (Ok(reparsed), Ok(expected)) => {
+                    assert_eq!(reparsed.ast(), expected, "{new_source:?}")
+                }
+                (Err(_), Err(_)) => {}
+                _ => panic!("{new_source:?}: {:?}", reparsed.as_ref().map(|_| ())),
+            }
+        }
+    }
+
+    #[test]
+    fn reparse_invalid_test() {
+        let parsed = ParsedSource::parse(DART_REPARSE.to_owned()).unwrap();
+
+        assert!(parsed.reparse(&[TextEdit::new(0..1, "{")]).is_err());
+    }
+}
@@ -1,20 +1,23 @@
-mod annotation;
-mod class;
+pub mod annotation;
+pub mod class;
 mod comment;
-mod common;
-mod directive;
+pub mod common;
+pub mod depth;
+pub mod directive;
 mod enum_ty;
-mod expr;
+pub mod expr;
 mod extension;
 mod func_call;
 mod func_like;
 mod maybe_required;
-mod meta;
+pub mod meta;
+pub mod skip;
 mod string;
-mod ty;
+pub mod ty;
 mod type_params;
 mod typedef;
 mod var;
+mod var_or_func_like;
 
 use std::str;
 
@@ -23,7 +26,7 @@ use nom::{
     combinator::{eof, opt},
     error::{ContextError, ParseError},
     multi::many0,
-    sequence::{pair, preceded, terminated},
+    sequence::terminated,
     Parser,
 };
 
@@ -35,40 +38,86 @@ use crate::{
 use self::{
     annotation::annotation,
     comment::comment,
-    common::{spbr, spbrc},
+    common::spbr,
     directive::directive,
     enum_ty::enum_ty,
     extension::extension,
-    func_like::func_like,
-    meta::with_meta,
+    meta::{dangling_comments, with_meta_trailing},
     typedef::typedef,
-    var::var,
+    var_or_func_like::{var_or_func_like, VarOrFuncLike},
 };
 
 type PResult<'s, T, E> = Result<(&'s str, T), nom::Err<E>>;
 
-pub fn parse<'s, E>(s: &'s str) -> PResult<Vec<WithMeta<Dart>>, E>
+pub fn parse<'s, E>(s: &'s str) -> PResult<'s, Vec<WithMeta<'s, Dart<'s>>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
-    preceded(
-        opt(spbr),
-        terminated(
-            many0(terminated(with_meta(dart_item), opt(spbr))),
-            pair(opt(spbrc), eof),
-        ),
-    )(s)
+    let (s, _) = opt(spbr)(s)?;
+
+    dart_items_to_end(s)
+}
+
+/// Parse a sequence of top-level items, consuming the trailing whitespace.
+pub fn dart_items<'s, E>(s: &'s str) -> PResult<'s, Vec<WithMeta<'s, Dart<'s>>>, E>
+where
+    E: ParseError<&'s str> + ContextError<&'s str>,
+{
+    many0(terminated(with_meta_trailing(dart_item), opt(spbr)))(s)
+}
+
+/// Parse the top-level items up to the end of the input.
+pub fn dart_items_to_end<'s, E>(s: &'s str) -> PResult<'s, Vec<WithMeta<'s, Dart<'s>>>, E>
+where
+    E: ParseError<&'s str> + ContextError<&'s str>,
+{
+    let (s, items) = many0(dart_item_with_end)(s)?;
+    // A file without any item has no node to keep its comments
+    let (s, _) = terminated(dangling_comments, eof)(s)?;
+
+    Ok((s, items))
+}
+
+/// Parse a single top-level item, consuming the trailing whitespace,
+/// or the end of the input, possibly preceded by dangling comments.
+///
+/// The leading whitespace of the input is expected to be consumed beforehand.
+pub fn dart_item_or_end<'s, E>(s: &'s str) -> PResult<'s, Option<WithMeta<'s, Dart<'s>>>, E>
+where
+    E: ParseError<&'s str> + ContextError<&'s str>,
+{
+    alt((
+        dart_item_with_end.map(Some),
+        terminated(dangling_comments, eof).map(|_| None),
+    ))(s)
 }
 
-fn dart_item<'s, E>(s: &'s str) -> PResult<Dart, E>
+/// Parse a top-level item, consuming the trailing whitespace.
+///
+/// The comments at the end of the input, not followed by any other item,
+/// are added to the trailing comments of the last item.
+fn dart_item_with_end<'s, E>(s: &'s str) -> PResult<'s, WithMeta<'s, Dart<'s>>, E>
+where
+    E: ParseError<&'s str> + ContextError<&'s str>,
+{
+    let (s, mut item) = terminated(with_meta_trailing(dart_item), opt(spbr))(s)?;
+    let (s, comments) = opt(terminated(dangling_comments, eof))(s)?;
+    item.trailing.extend(comments.into_iter().flatten());
+
+    Ok((s, item))
+}
+
+fn dart_item<'s, E>(s: &'s str) -> PResult<'s, Dart<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
     alt((
         directive.map(Dart::Directive),
         typedef.map(Dart::TypeDef),
-        var.map(Dart::Var),
-        func_like.map(Dart::FuncLike),
+        var_or_func_like.map(|item| match item {
+            VarOrFuncLike::Var(var) => Dart::Var(var),
+            VarOrFuncLike::FuncLike(func_like) => Dart::FuncLike(func_like),
+        }),
         class.map(Dart::Class),
         enum_ty.map(Dart::Enum),
         extension.map(Dart::Extension),
@@ -161,16 +210,17 @@ mod tests {
                                 initializer: None,
                             })),
                         ],
+                        dangling_comments: Vec::new(),
                     })),
                     WithMeta::new(
                         vec![Meta::Annotation(Annotation::Ident("immutable"))],
                         Dart::Class(Class {
                             modifiers: ClassModifierSet::from_iter([ClassModifier::Class]),
                             name: "Record",
-                            type_params: vec![TypeParam {
+                            type_params: vec![WithMeta::value(TypeParam {
                                 name: "T",
                                 extends: None
-                            }],
+                            })],
                             extends: Some(NotFuncType::name("Base")),
                             with: Vec::new(),
                             implements: vec![
@@ -201,6 +251,7 @@ mod tests {
                                 name: "name",
                                 initializer: None,
                             })),],
+                            dangling_comments: Vec::new(),
                         })
                     ),
                     WithMeta::value(Dart::FuncLike(FuncLike::Func(Func {
@@ -245,6 +296,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn comments_test() {
+        let (_, items) = parse::<VerboseError<_>>(
+            "import 'a.dart'; // Trailing\n// Leading\nint x;\n\n// Dangling\n/* End */\n",
+        )
+        .unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(
+            items[0].trailing,
+            vec![Comment::SingleLine("// Trailing\n")]
+        );
+        assert_eq!(
+            items[1].meta.as_slice(),
+            [Meta::Comment(Comment::SingleLine("// Leading\n"))]
+        );
+        assert_eq!(
+            items[1].trailing,
+            vec![
+                Comment::SingleLine("// Dangling\n"),
+                Comment::MultiLine("/* End */")
+            ]
+        );
+    }
+
     const DART_MIXED: &str = r#"
 import 'dart:math';
 import 'package:path/path.dart' as p;
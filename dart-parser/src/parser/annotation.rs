@@ -11,7 +11,7 @@ use crate::dart::Annotation;
 
 use super::{func_call::annotation_func_call, ty::identifier, PResult};
 
-pub fn annotation<'s, E>(s: &'s str) -> PResult<Annotation, E>
+pub fn annotation<'s, E>(s: &'s str) -> PResult<'s, Annotation<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -10,21 +10,21 @@ use nom::{
 
 use crate::dart::{
     class::{ClassMember, ClassModifier, ClassModifierSet, Constructor, ConstructorModifier},
-    Class, NotFuncType, WithMeta,
+    Class, Comment, NotFuncType, WithMeta,
 };
 
 use super::{
     common::*,
     expr::expr,
-    func_like::{func_body_content, func_like, func_params},
-    meta::with_meta,
+    func_like::{func_body_content, func_params},
+    meta::{dangling_comments, with_meta_trailing},
     ty::{identifier, not_func_type},
     type_params::type_params,
-    var::var,
+    var_or_func_like::{var_or_func_like, VarOrFuncLike},
     PResult,
 };
 
-pub fn class<'s, E>(s: &'s str) -> PResult<Class, E>
+pub fn class<'s, E>(s: &'s str) -> PResult<'s, Class<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -41,21 +41,24 @@ where
             class_body,
         ))
         .map(
-            |(modifiers, name, type_params, extends, with, implements, on, body)| Class {
-                modifiers,
-                name,
-                type_params: type_params.unwrap_or(Vec::new()),
-                extends,
-                with: with.unwrap_or(Vec::new()),
-                implements: implements.unwrap_or(Vec::new()),
-                mixin_on: on.unwrap_or(Vec::new()),
-                body,
+            |(modifiers, name, type_params, extends, with, implements, on, (body, dangling))| {
+                Class {
+                    modifiers,
+                    name,
+                    type_params: type_params.unwrap_or(Vec::new()),
+                    extends,
+                    with: with.unwrap_or(Vec::new()),
+                    implements: implements.unwrap_or(Vec::new()),
+                    mixin_on: on.unwrap_or(Vec::new()),
+                    body,
+                    dangling_comments: dangling,
+                }
             },
         ),
     )(s)
 }
 
-fn class_modifier_set<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<ClassModifierSet, E> {
+fn class_modifier_set<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<'s, ClassModifierSet, E> {
     let (s, modifier) = class_modifier(s)?;
 
     let modifiers = ClassModifierSet::from_iter([modifier]);
@@ -67,7 +70,7 @@ fn class_modifier_set<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<ClassMo
     )(s)
 }
 
-fn class_modifier<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<ClassModifier, E> {
+fn class_modifier<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<'s, ClassModifier, E> {
     alt((
         value(ClassModifier::Abstract, tag("abstract")),
         value(ClassModifier::Base, tag("base")),
@@ -79,7 +82,7 @@ fn class_modifier<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<ClassModifi
     ))(s)
 }
 
-fn extends_clause<'s, E>(s: &'s str) -> PResult<NotFuncType, E>
+fn extends_clause<'s, E>(s: &'s str) -> PResult<'s, NotFuncType<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -89,7 +92,7 @@ where
     )(s)
 }
 
-pub fn implements_clause<'s, E>(s: &'s str) -> PResult<Vec<NotFuncType>, E>
+pub fn implements_clause<'s, E>(s: &'s str) -> PResult<'s, Vec<NotFuncType<'s>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -105,7 +108,7 @@ where
     )(s)
 }
 
-pub fn mixin_on_clause<'s, E>(s: &'s str) -> PResult<Vec<NotFuncType>, E>
+pub fn mixin_on_clause<'s, E>(s: &'s str) -> PResult<'s, Vec<NotFuncType<'s>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -121,7 +124,7 @@ where
     )(s)
 }
 
-pub fn with_clause<'s, E>(s: &'s str) -> PResult<Vec<NotFuncType>, E>
+pub fn with_clause<'s, E>(s: &'s str) -> PResult<'s, Vec<NotFuncType<'s>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -137,7 +140,10 @@ where
     )(s)
 }
 
-fn class_body<'s, E>(s: &'s str) -> PResult<Vec<WithMeta<ClassMember>>, E>
+#[allow(clippy::type_complexity)]
+fn class_body<'s, E>(
+    s: &'s str,
+) -> PResult<'s, (Vec<WithMeta<'s, ClassMember<'s>>>, Vec<Comment<'s>>), E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -145,26 +151,33 @@ where
         "class_body",
         preceded(
             pair(tag("{"), opt(spbr)),
-            cut(terminated(
-                many0(terminated(with_meta(class_member), opt(spbr))),
-                pair(opt(spbrc), tag("}")),
-            )),
+            cut(pair(class_members, terminated(dangling_comments, tag("}")))),
         ),
     )(s)
 }
 
-pub fn class_member<'s, E>(s: &'s str) -> PResult<ClassMember, E>
+/// Parse a sequence of class members, consuming the trailing whitespace.
+pub fn class_members<'s, E>(s: &'s str) -> PResult<'s, Vec<WithMeta<'s, ClassMember<'s>>>, E>
+where
+    E: ParseError<&'s str> + ContextError<&'s str>,
+{
+    many0(terminated(with_meta_trailing(class_member), opt(spbr)))(s)
+}
+
+pub fn class_member<'s, E>(s: &'s str) -> PResult<'s, ClassMember<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
     alt((
         constructor.map(ClassMember::Constructor),
-        var.map(ClassMember::Var),
-        func_like.map(ClassMember::FuncLike),
+        var_or_func_like.map(|member| match member {
+            VarOrFuncLike::Var(var) => ClassMember::Var(var),
+            VarOrFuncLike::FuncLike(func_like) => ClassMember::FuncLike(func_like),
+        }),
     ))(s)
 }
 
-fn constructor<'s, E>(s: &'s str) -> PResult<Constructor, E>
+fn constructor<'s, E>(s: &'s str) -> PResult<'s, Constructor<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -186,7 +199,7 @@ where
     )(s)
 }
 
-fn constructor_modifier<'s, E>(s: &'s str) -> PResult<ConstructorModifier, E>
+fn constructor_modifier<'s, E>(s: &'s str) -> PResult<'s, ConstructorModifier, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -197,7 +210,7 @@ where
     ))(s)
 }
 
-fn constructor_initializer_list<'s, E>(s: &'s str) -> PResult<&str, E>
+fn constructor_initializer_list<'s, E>(s: &'s str) -> PResult<'s, &'s str, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -277,20 +290,21 @@ mod tests {
                     modifiers: ClassModifierSet::from_iter([ClassModifier::Class]),
                     name: "Record",
                     type_params: vec![
-                        TypeParam {
+                        WithMeta::value(TypeParam {
                             name: "T",
                             extends: None
-                        },
-                        TypeParam {
+                        }),
+                        WithMeta::value(TypeParam {
                             name: "U",
                             extends: Some(Type::NotFunc(NotFuncType::name("Object")))
-                        },
+                        }),
                     ],
                     extends: Some(NotFuncType::name("Base")),
                     with: Vec::new(),
                     implements: vec![NotFuncType::name("A"), NotFuncType::name("B")],
                     mixin_on: Vec::default(),
                     body: Vec::new(),
+                    dangling_comments: Vec::new(),
                 }
             ))
         );
@@ -319,11 +333,36 @@ mod tests {
                             initializer: None,
                         }),
                     )],
+                    dangling_comments: Vec::new(),
                 }
             ))
         );
     }
 
+    #[test]
+    fn class_comments_test() {
+        let (_, class) = class::<VerboseError<_>>(
+            "class A {\n  int x; // legacy\n  // Leading\n  int y;\n  // Dangling\n}",
+        )
+        .unwrap();
+
+        assert_eq!(class.body.len(), 2);
+        assert_eq!(class.body[0].meta.len(), 0);
+        assert_eq!(
+            class.body[0].trailing,
+            vec![Comment::SingleLine("// legacy\n")]
+        );
+        assert_eq!(
+            class.body[1].meta.as_slice(),
+            [Meta::Comment(Comment::SingleLine("// Leading\n"))]
+        );
+        assert_eq!(class.body[1].trailing, Vec::new());
+        assert_eq!(
+            class.dangling_comments,
+            vec![Comment::SingleLine("// Dangling\n")]
+        );
+    }
+
     #[test]
     fn class_generic_base_test() {
         assert_eq!(
@@ -351,6 +390,7 @@ mod tests {
                     }],
                     mixin_on: Vec::default(),
                     body: Vec::new(),
+                    dangling_comments: Vec::new(),
                 }
             ))
         );
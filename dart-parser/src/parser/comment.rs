@@ -10,13 +10,16 @@ use nom::{
     Parser,
 };
 
-use crate::{dart::comment::Comment, parser::common::*};
+use crate::{
+    dart::comment::Comment,
+    parser::{common::*, depth::nested},
+};
 
 use super::PResult;
 
 /// The single-line comment parser consumes the trailing line-break, because
 /// that line-break terminates the comment rather than being "just" whitespace.
-pub fn comment<'s, E>(s: &'s str) -> PResult<Comment, E>
+pub fn comment<'s, E>(s: &'s str) -> PResult<'s, Comment<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -26,7 +29,7 @@ where
     ))(s)
 }
 
-fn comment_single_line<'s, E>(s: &'s str) -> PResult<&str, E>
+fn comment_single_line<'s, E>(s: &'s str) -> PResult<'s, &'s str, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -42,11 +45,11 @@ where
     )(s)
 }
 
-fn comment_multi_line<'s, E>(s: &'s str) -> PResult<&str, E>
+fn comment_multi_line<'s, E>(s: &'s str) -> PResult<'s, &'s str, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
-    context(
+    nested(context(
         "comment_multi_line",
         recognize(preceded(
             tag("/*"),
@@ -60,7 +63,7 @@ where
                 tag("*/"),
             )),
         )),
-    )(s)
+    ))(s)
 }
 
 #[cfg(test)]
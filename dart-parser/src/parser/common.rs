@@ -111,7 +111,7 @@ pub enum SepMode {
 /// Parse whitespace, including line breaks, and comments.
 ///
 /// For use in positions where comments are not collected as metadata.
-pub fn spbrc<'s, E>(s: &'s str) -> PResult<&str, E>
+pub fn spbrc<'s, E>(s: &'s str) -> PResult<'s, &'s str, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -119,16 +119,21 @@ where
 }
 
 /// Parse one or more whitespace characters, including line breaks.
-pub fn spbr<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<&str, E> {
+pub fn spbr<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<'s, &'s str, E> {
     is_a(" \t\r\n")(s)
 }
 
-pub fn spbr_char<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<char, E> {
+/// Parse one or more whitespace characters, excluding line breaks.
+pub fn sp<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<'s, &'s str, E> {
+    is_a(" \t")(s)
+}
+
+pub fn spbr_char<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<'s, char, E> {
     one_of(" \t\r\n")(s)
 }
 
 /// Parse exactly one line break.
-pub fn br<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<&str, E> {
+pub fn br<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<'s, &'s str, E> {
     alt((tag("\n"), tag("\r\n"), tag("\r")))(s)
 }
 
@@ -0,0 +1,101 @@
+use std::cell::Cell;
+
+use nom::{
+    error::{ErrorKind, ParseError},
+    Parser,
+};
+
+use super::PResult;
+
+/// The default limit on the nesting of brackets, types and comments.
+///
+/// A 2 MiB stack (the default for spawned threads) holds about 270 levels
+/// of nested expressions and 160 levels of nested types in a debug build,
+/// and about 2,200 and 1,100 levels in a release build.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+thread_local! {
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    static MAX_DEPTH: Cell<usize> = const { Cell::new(DEFAULT_MAX_DEPTH) };
+}
+
+/// Run `f` with the nesting depth limited to `max_depth`.
+pub fn with_max_depth<T>(max_depth: usize, f: impl FnOnce() -> T) -> T {
+    let prev = MAX_DEPTH.with(|max| max.replace(max_depth));
+    let _restore = Restore(prev);
+
+    f()
+}
+
+/// The number of nesting levels left before the limit is reached.
+pub fn remaining_depth() -> usize {
+    MAX_DEPTH
+        .with(Cell::get)
+        .saturating_sub(DEPTH.with(Cell::get))
+}
+
+/// Run a recursive parser one nesting level deeper.
+///
+/// Fail irrecoverably once the maximum depth is exceeded, so a deeply nested
+/// input ends in a parse error instead of a stack overflow.
+pub fn nested<'s, P, O, E>(mut p: P) -> impl FnMut(&'s str) -> PResult<'s, O, E>
+where
+    P: Parser<&'s str, O, E>,
+    E: ParseError<&'s str>,
+{
+    move |s| {
+        let _level = Level::enter()
+            .ok_or_else(|| nom::Err::Failure(E::from_error_kind(s, ErrorKind::TooLarge)))?;
+
+        p.parse(s)
+    }
+}
+
+struct Level;
+
+impl Level {
+    fn enter() -> Option<Self> {
+        (remaining_depth() > 0).then(|| {
+            DEPTH.with(|depth| depth.set(depth.get() + 1));
+            Self
+        })
+    }
+}
+
+impl Drop for Level {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+struct Restore(usize);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        MAX_DEPTH.with(|max| max.set(self.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::{
+        character::complete::char, combinator::opt, error::VerboseError, sequence::delimited,
+    };
+
+    use super::*;
+
+    fn parens(s: &str) -> PResult<'_, (), VerboseError<&str>> {
+        nested(delimited(char('('), opt(parens), char(')')))(s).map(|(s, _)| (s, ()))
+    }
+
+    #[test]
+    fn nested_test() {
+        // Looking for a nested scope takes a level as well
+        assert!(with_max_depth(3, || parens("(())")).is_ok());
+        assert!(matches!(
+            with_max_depth(3, || parens("((()))")),
+            Err(nom::Err::Failure(_))
+        ));
+        assert_eq!(remaining_depth(), DEFAULT_MAX_DEPTH);
+    }
+}
@@ -1,7 +1,8 @@
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    combinator::{cut, opt},
+    character::complete::satisfy,
+    combinator::{cut, not, opt},
     error::{context, ContextError, ParseError},
     multi::separated_list1,
     sequence::{pair, preceded, terminated, tuple},
@@ -17,13 +18,14 @@ use super::{
     PResult,
 };
 
-pub fn directive<'s, E>(s: &'s str) -> PResult<Directive, E>
+pub fn directive<'s, E>(s: &'s str) -> PResult<'s, Directive<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
     context(
         "directive",
         alt((
+            library.map(Directive::Library),
             export.map(Directive::Export),
             import.map(Directive::Import),
             part_of.map(Directive::PartOf),
@@ -32,7 +34,28 @@ where
     )(s)
 }
 
-fn export<'s, E>(s: &'s str) -> PResult<Export, E>
+fn library<'s, E>(s: &'s str) -> PResult<'s, Option<&'s str>, E>
+where
+    E: ParseError<&'s str> + ContextError<&'s str>,
+{
+    context(
+        "library",
+        preceded(
+            terminated(
+                tag("library"),
+                not(satisfy(|c| {
+                    c.is_ascii_alphanumeric() || c == '_' || c == '$'
+                })),
+            ),
+            cut(terminated(
+                opt(preceded(spbr, identifier)),
+                pair(opt(spbr), tag(";")),
+            )),
+        ),
+    )(s)
+}
+
+fn export<'s, E>(s: &'s str) -> PResult<'s, Export<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -49,7 +72,7 @@ where
     )(s)
 }
 
-fn import<'s, E>(s: &'s str) -> PResult<Import, E>
+fn import<'s, E>(s: &'s str) -> PResult<'s, Import<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -78,7 +101,7 @@ where
     .parse(s)
 }
 
-fn import_filters<'s, E>(s: &'s str) -> PResult<Vec<Filter>, E>
+fn import_filters<'s, E>(s: &'s str) -> PResult<'s, Vec<Filter<'s>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -93,7 +116,7 @@ where
     )(s)
 }
 
-fn show_clause<'s, E>(s: &'s str) -> PResult<Vec<&str>, E>
+fn show_clause<'s, E>(s: &'s str) -> PResult<'s, Vec<&'s str>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -103,7 +126,7 @@ where
     )(s)
 }
 
-fn hide_clause<'s, E>(s: &'s str) -> PResult<Vec<&str>, E>
+fn hide_clause<'s, E>(s: &'s str) -> PResult<'s, Vec<&'s str>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -113,7 +136,7 @@ where
     )(s)
 }
 
-fn part<'s, E>(s: &'s str) -> PResult<&str, E>
+fn part<'s, E>(s: &'s str) -> PResult<'s, &'s str, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -126,7 +149,7 @@ where
     )(s)
 }
 
-fn part_of<'s, E>(s: &'s str) -> PResult<PartOf, E>
+fn part_of<'s, E>(s: &'s str) -> PResult<'s, PartOf<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -148,6 +171,16 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn library_test() {
+        assert_eq!(
+            library::<VerboseError<_>>("library app.models;x"),
+            Ok(("x", Some("app.models")))
+        );
+        assert_eq!(library::<VerboseError<_>>("library;x"), Ok(("x", None)));
+        assert!(library::<VerboseError<_>>("libraryName = 0;").is_err());
+    }
+
     #[test]
     fn export_test() {
         assert_eq!(
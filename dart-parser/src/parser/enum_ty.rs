@@ -8,18 +8,18 @@ use nom::{
     Parser,
 };
 
-use crate::dart::{class::ClassMember, enum_ty::EnumValue, EnumTy, WithMeta};
+use crate::dart::{class::ClassMember, enum_ty::EnumValue, Comment, EnumTy, WithMeta};
 
 use super::{
-    class::{class_member, implements_clause},
-    common::{sep_list, spbr, spbrc, SepMode},
+    class::{class_members, implements_clause},
+    common::{spbr, spbrc},
     func_call::func_args,
-    meta::with_meta,
+    meta::{dangling_comments, trailing_comments, with_meta},
     ty::identifier,
     PResult,
 };
 
-pub fn enum_ty<'s, E>(s: &'s str) -> PResult<EnumTy, E>
+pub fn enum_ty<'s, E>(s: &'s str) -> PResult<'s, EnumTy<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -31,19 +31,30 @@ where
             enum_body,
         )),
     )
-    .map(|(name, implements, (values, members))| EnumTy {
-        name,
-        implements: implements.unwrap_or(Vec::new()),
-        values,
-        members,
-    })
+    .map(
+        |(name, implements, (values, members, dangling_comments))| EnumTy {
+            name,
+            implements: implements.unwrap_or(Vec::new()),
+            values,
+            members,
+            dangling_comments,
+        },
+    )
     .parse(s)
 }
 
 #[allow(clippy::type_complexity)]
 fn enum_body<'s, E>(
     s: &'s str,
-) -> PResult<(Vec<WithMeta<EnumValue>>, Vec<WithMeta<ClassMember>>), E>
+) -> PResult<
+    's,
+    (
+        Vec<WithMeta<'s, EnumValue<'s>>>,
+        Vec<WithMeta<'s, ClassMember<'s>>>,
+        Vec<Comment<'s>>,
+    ),
+    E,
+>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -52,43 +63,71 @@ where
         preceded(
             pair(tag("{"), opt(spbr)),
             cut(terminated(
-                pair(
-                    terminated(
-                        sep_list(
-                            0,
-                            SepMode::AllowTrailing,
-                            pair(tag(","), opt(spbr)),
-                            terminated(with_meta(enum_value), opt(spbrc)),
-                        ),
-                        opt(spbrc),
-                    ),
+                tuple((
+                    enum_values,
+                    dangling_comments,
                     alt((
                         preceded(
                             pair(tag(";"), opt(spbr)),
-                            many0(terminated(with_meta(class_member), opt(spbr))),
+                            pair(class_members, dangling_comments),
                         ),
-                        success(()).map(|_| Vec::new()),
+                        success(()).map(|_| (Vec::new(), Vec::new())),
                     )),
-                ),
+                )),
                 tag("}"),
             )),
         ),
-    )(s)
+    )
+    .map(|(values, mut dangling, (members, members_dangling))| {
+        dangling.extend(members_dangling);
+        (values, members, dangling)
+    })
+    .parse(s)
 }
 
-fn enum_value<'s, E>(s: &'s str) -> PResult<EnumValue, E>
+/// Parse the comma-separated enum values, consuming the trailing whitespace.
+///
+/// The trailing comments of a value follow its comma.
+fn enum_values<'s, E>(s: &'s str) -> PResult<'s, Vec<WithMeta<'s, EnumValue<'s>>>, E>
+where
+    E: ParseError<&'s str> + ContextError<&'s str>,
+{
+    pair(
+        many0(terminated(
+            pair(
+                terminated(with_meta(enum_value), pair(opt(spbrc), tag(","))),
+                trailing_comments,
+            ),
+            opt(spbr),
+        )),
+        opt(terminated(
+            pair(with_meta(enum_value), trailing_comments),
+            opt(spbr),
+        )),
+    )
+    .map(|(values, last): (Vec<_>, _)| {
+        values
+            .into_iter()
+            .chain(last)
+            .map(|(value, trailing)| value.with_trailing(trailing))
+            .collect()
+    })
+    .parse(s)
+}
+
+fn enum_value<'s, E>(s: &'s str) -> PResult<'s, EnumValue<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
     context(
         "enum_value",
-        tuple((
-            terminated(identifier, opt(spbr)),
+        pair(
+            identifier,
             alt((
-                terminated(func_args, opt(spbr)),
+                preceded(opt(spbr), func_args),
                 success(()).map(|_| Vec::new()),
             )),
-        ))
+        )
         .map(|(name, params)| EnumValue { name, args: params }),
     )(s)
 }
@@ -115,6 +154,7 @@ mod tests {
                         args: Vec::new(),
                     })],
                     members: Vec::new(),
+                    dangling_comments: Vec::new(),
                 }
             ))
         );
@@ -145,11 +185,52 @@ mod tests {
                         }
                     )],
                     members: Vec::new(),
+                    dangling_comments: Vec::new(),
                 }
             ))
         );
     }
 
+    #[test]
+    fn enum_comments_test() {
+        let (_, enum_ty) = enum_ty::<VerboseError<_>>(
+            "enum E {\n  a, // First\n  b(1) /* Last */\n  // After values\n}x",
+        )
+        .unwrap();
+
+        assert_eq!(enum_ty.values.len(), 2);
+        assert_eq!(
+            enum_ty.values[0].trailing,
+            vec![Comment::SingleLine("// First\n")]
+        );
+        assert_eq!(
+            enum_ty.values[1].trailing,
+            vec![Comment::MultiLine("/* Last */")]
+        );
+        assert_eq!(
+            enum_ty.dangling_comments,
+            vec![Comment::SingleLine("// After values\n")]
+        );
+    }
+
+    #[test]
+    fn enum_members_comments_test() {
+        let (_, enum_ty) = enum_ty::<VerboseError<_>>(
+            "enum E {\n  a;\n\n  final int x = 0; // Member\n  // After members\n}x",
+        )
+        .unwrap();
+
+        assert_eq!(enum_ty.values.len(), 1);
+        assert_eq!(
+            enum_ty.members[0].trailing,
+            vec![Comment::SingleLine("// Member\n")]
+        );
+        assert_eq!(
+            enum_ty.dangling_comments,
+            vec![Comment::SingleLine("// After members\n")]
+        );
+    }
+
     #[test]
     fn enum_implements_test() {
         assert_eq!(
@@ -164,6 +245,7 @@ mod tests {
                         args: Vec::new(),
                     })],
                     members: Vec::new(),
+                    dangling_comments: Vec::new(),
                 }
             ))
         );
@@ -1,8 +1,8 @@
 use nom::{
     branch::alt,
-    combinator::{eof, opt, recognize},
+    combinator::{eof, not, opt, peek, recognize},
     error::{ContextError, ParseError},
-    sequence::{preceded, terminated},
+    sequence::{pair, preceded, terminated},
     Parser,
 };
 
@@ -12,14 +12,19 @@ use super::{comment, common::uncut, string::string, ty::identifier, PResult};
 
 use nom::{
     bytes::complete::{is_not, tag},
-    character::complete::char,
+    character::complete::{char, one_of},
     combinator::{cut, success},
     error::context,
 };
 
-use super::{common::skip_many1, ty::type_args};
+use super::{
+    common::skip_many1,
+    depth::{nested, remaining_depth},
+    skip::block_end,
+    ty::type_args,
+};
 
-pub fn expr<'s, E>(s: &'s str) -> PResult<Expr, E>
+pub fn expr<'s, E>(s: &'s str) -> PResult<'s, Expr<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -31,21 +36,32 @@ where
         )
         .and_then(alt((
             terminated(identifier, eof).map(Expr::Ident),
-            terminated(string, eof).map(Expr::String),
+            // Only the single-line non-raw strings, see `Expr::String`
+            preceded(
+                pair(not(alt((tag("'''"), tag("\"\"\"")))), peek(one_of("'\""))),
+                terminated(string, eof),
+            )
+            .map(Expr::String),
             |s: &'s str| Ok((&s[s.len()..], Expr::Verbatim(s))),
         ))),
     )
     .parse(s)
 }
 
-pub fn block<'s, E>(s: &'s str) -> PResult<&str, E>
+pub fn block<'s, E>(s: &'s str) -> PResult<'s, &'s str, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
-    recognize(scope('{', '}'))(s)
+    let (body, _) = char('{')(s)?;
+
+    match block_end(body.as_bytes(), remaining_depth()) {
+        Some(len) => Ok((&body[len..], &s[..len + 1])),
+        // Let the thorough parser report the error
+        None => recognize(scope('{', '}'))(s),
+    }
 }
 
-fn any_scope<'s, E>(s: &'s str) -> PResult<&str, E>
+fn any_scope<'s, E>(s: &'s str) -> PResult<'s, &'s str, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -57,25 +73,25 @@ fn scope<'s, E>(open: char, close: char) -> impl FnMut(&'s str) -> PResult<&'s s
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
-    context(
+    nested(context(
         "scope",
         preceded(
             char(open),
             cut(terminated(alt((scope_body, success(""))), char(close))),
         ),
-    )
+    ))
 }
 
 const SCOPE_STOP_CHARS: &str = "<>()[]{}=/r'\"";
 
-fn scope_body<'s, E>(s: &'s str) -> PResult<&'s str, E>
+fn scope_body<'s, E>(s: &'s str) -> PResult<'s, &'s str, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
     recognize(skip_many1(alt((is_not(SCOPE_STOP_CHARS), body_item))))(s)
 }
 
-fn expr_body<'s, E>(s: &'s str) -> PResult<&'s str, E>
+fn expr_body<'s, E>(s: &'s str) -> PResult<'s, &'s str, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -85,7 +101,7 @@ where
     recognize(skip_many1(alt((is_not(EXPR_STOP_CHARS), body_item))))(s)
 }
 
-fn body_item<'s, E>(s: &'s str) -> PResult<&'s str, E>
+fn body_item<'s, E>(s: &'s str) -> PResult<'s, &'s str, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
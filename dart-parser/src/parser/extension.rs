@@ -8,19 +8,18 @@ use nom::{
     Parser,
 };
 
-use crate::dart::{extension::ExtensionMember, Extension};
+use crate::dart::{extension::ExtensionMember, Comment, Extension, WithMeta};
 
 use super::{
-    annotation::annotation,
-    comment::comment,
     common::spbr,
-    func_like::func_like,
+    meta::{dangling_comments, with_meta_trailing},
     ty::{identifier, ty},
     type_params::type_params,
-    var, PResult,
+    var_or_func_like::{var_or_func_like, VarOrFuncLike},
+    PResult,
 };
 
-pub fn extension<'s, E>(s: &'s str) -> PResult<Extension, E>
+pub fn extension<'s, E>(s: &'s str) -> PResult<'s, Extension<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -51,16 +50,22 @@ where
             terminated(ty, opt(spbr)),
             extension_body,
         ))
-        .map(|((name, type_params), on, body)| Extension {
-            name,
-            type_params,
-            on,
-            body,
-        }),
+        .map(
+            |((name, type_params), on, (body, dangling_comments))| Extension {
+                name,
+                type_params,
+                on,
+                body,
+                dangling_comments,
+            },
+        ),
     )(s)
 }
 
-pub fn extension_body<'s, E>(s: &'s str) -> PResult<Vec<ExtensionMember>, E>
+#[allow(clippy::type_complexity)]
+pub fn extension_body<'s, E>(
+    s: &'s str,
+) -> PResult<'s, (Vec<WithMeta<'s, ExtensionMember<'s>>>, Vec<Comment<'s>>), E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -68,31 +73,31 @@ where
         "extension_body",
         preceded(
             pair(tag("{"), opt(spbr)),
-            cut(terminated(
-                many0(terminated(extension_member, opt(spbr))),
-                tag("}"),
+            cut(pair(
+                many0(terminated(with_meta_trailing(extension_member), opt(spbr))),
+                terminated(dangling_comments, tag("}")),
             )),
         ),
     )(s)
 }
 
-fn extension_member<'s, E>(s: &'s str) -> PResult<ExtensionMember, E>
+fn extension_member<'s, E>(s: &'s str) -> PResult<'s, ExtensionMember<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
-    alt((
-        comment.map(ExtensionMember::Comment),
-        annotation.map(ExtensionMember::Annotation),
-        func_like.map(ExtensionMember::FuncLike),
-        var.map(ExtensionMember::Var),
-    ))(s)
+    var_or_func_like
+        .map(|member| match member {
+            VarOrFuncLike::Var(var) => ExtensionMember::Var(var),
+            VarOrFuncLike::FuncLike(func_like) => ExtensionMember::FuncLike(func_like),
+        })
+        .parse(s)
 }
 
 #[cfg(test)]
 mod tests {
     use nom::error::VerboseError;
 
-    use crate::dart::{ty::Type, NotFuncType};
+    use crate::dart::{meta::Meta, ty::Type, Annotation, FuncLike, NotFuncType};
 
     use super::*;
 
@@ -111,6 +116,7 @@ mod tests {
                         is_nullable: false
                     }),
                     body: Vec::new(),
+                    dangling_comments: Vec::new(),
                 }
             ))
         );
@@ -131,8 +137,35 @@ mod tests {
                         is_nullable: false
                     }),
                     body: Vec::new(),
+                    dangling_comments: Vec::new(),
                 }
             ))
         );
     }
+
+    #[test]
+    fn extension_meta_test() {
+        let (_, extension) = extension::<VerboseError<_>>(
+            "extension X on Y {\n  @override\n  int get x => 0; // Zero\n  // Dangling\n}x",
+        )
+        .unwrap();
+
+        assert_eq!(extension.body.len(), 1);
+        assert_eq!(
+            extension.body[0].meta.as_slice(),
+            [Meta::Annotation(Annotation::Ident("override"))]
+        );
+        assert!(matches!(
+            extension.body[0].as_ref(),
+            ExtensionMember::FuncLike(FuncLike::Getter(_))
+        ));
+        assert_eq!(
+            extension.body[0].trailing,
+            vec![Comment::SingleLine("// Zero\n")]
+        );
+        assert_eq!(
+            extension.dangling_comments,
+            vec![Comment::SingleLine("// Dangling\n")]
+        );
+    }
 }
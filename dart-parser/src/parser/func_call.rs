@@ -16,7 +16,7 @@ use super::{
     PResult,
 };
 
-pub fn _func_call<'s, E>(s: &'s str) -> PResult<FuncCall, E>
+pub fn _func_call<'s, E>(s: &'s str) -> PResult<'s, FuncCall<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -33,7 +33,7 @@ where
 /// When an annotation is applied to a function declaration, this helps
 /// differentiating between the annotation arguments and the return type of the
 /// annotated function (which may be a tuple).
-pub fn annotation_func_call<'s, E>(s: &'s str) -> PResult<FuncCall, E>
+pub fn annotation_func_call<'s, E>(s: &'s str) -> PResult<'s, FuncCall<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -43,7 +43,7 @@ where
     )(s)
 }
 
-pub fn func_args<'s, E>(s: &'s str) -> PResult<Vec<FuncArg>, E>
+pub fn func_args<'s, E>(s: &'s str) -> PResult<'s, Vec<FuncArg<'s>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -64,7 +64,7 @@ where
     )(s)
 }
 
-pub fn func_arg<'s, E>(s: &'s str) -> PResult<FuncArg, E>
+pub fn func_arg<'s, E>(s: &'s str) -> PResult<'s, FuncArg<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -2,7 +2,7 @@ use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::char,
-    combinator::{cut, opt, success, value},
+    combinator::{consumed, cut, opt, success, value},
     error::{context, ContextError, ParseError},
     multi::fold_many0,
     sequence::{pair, preceded, terminated, tuple},
@@ -11,11 +11,12 @@ use nom::{
 
 use crate::dart::{
     func_like::{
-        Func, FuncBody, FuncBodyContent, FuncBodyModifier, FuncModifier, FuncModifierSet,
-        FuncParam, FuncParamModifier, FuncParamModifierSet, FuncParams, FuncParamsExtra, Getter,
-        Operator, Setter, UserDefOperator,
+        Func, FuncBody, FuncBodyContent, FuncBodyModifier, FuncModifierSet, FuncParam,
+        FuncParamModifier, FuncParamModifierSet, FuncParams, FuncParamsExtra, Getter, Operator,
+        Setter, UserDefOperator,
     },
-    FuncLike, MaybeRequired, WithMeta,
+    ty::Type,
+    FuncLike, MaybeRequired, TypeParam, WithMeta,
 };
 
 use super::{
@@ -29,139 +30,140 @@ use super::{
     PResult,
 };
 
-pub fn func_like<'s, E>(s: &'s str) -> PResult<FuncLike, E>
+/// The part of a function-like declaration that follows the return type
+/// and tells what kind of declaration it is.
+pub enum FuncLikeName<'s> {
+    Func(&'s str),
+    Operator(&'s str, UserDefOperator),
+    Getter(&'s str),
+}
+
+pub fn func_like_name<'s, E>(s: &'s str) -> PResult<'s, FuncLikeName<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
-    context(
-        "func_like",
-        alt((
-            operator.map(FuncLike::Operator),
-            func.map(FuncLike::Func),
-            getter.map(FuncLike::Getter),
-            setter.map(FuncLike::Setter),
-        )),
-    )
-    .parse(s)
+    alt((
+        preceded(
+            pair(tag("operator"), opt(spbr)),
+            consumed(user_def_operator),
+        )
+        .map(|(name, operator_type)| FuncLikeName::Operator(name, operator_type)),
+        preceded(pair(tag("get"), spbr), identifier).map(FuncLikeName::Getter),
+        identifier.map(FuncLikeName::Func),
+    ))(s)
 }
 
-fn func<'s, E>(s: &'s str) -> PResult<Func, E>
+/// Parse the rest of a function-like declaration, once the modifiers,
+/// the return type and the name are parsed.
+pub fn func_like_rest<'s, E>(
+    s: &'s str,
+    modifiers: FuncModifierSet,
+    return_type: Type<'s>,
+    name: FuncLikeName<'s>,
+) -> PResult<'s, FuncLike<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
-    context(
-        "func",
-        tuple((
-            alt((
-                terminated(func_modifier_set, spbr),
-                success(FuncModifierSet::default()),
-            )),
-            // Return type
-            terminated(ty, opt(spbr)),
-            // Function name
-            terminated(identifier, opt(spbr)),
-            opt(terminated(type_params, opt(spbr))),
-            terminated(func_params, opt(spbr)),
-            alt((func_body.map(Some), tag(";").map(|_| None))),
-        ))
-        .map(
-            |(modifiers, return_type, name, type_params, params, body)| Func {
-                modifiers,
-                return_type,
-                name,
-                type_params: type_params.unwrap_or(Vec::new()),
-                params,
-                body,
-            },
-        ),
-    )
-    .parse(s)
+    match name {
+        FuncLikeName::Func(name) => {
+            let (s, (type_params, params, body)) = context("func", func_signature_rest)(s)?;
+
+            Ok((
+                s,
+                FuncLike::Func(Func {
+                    modifiers,
+                    return_type,
+                    name,
+                    type_params,
+                    params,
+                    body,
+                }),
+            ))
+        }
+        FuncLikeName::Operator(name, operator_type) => {
+            let (s, (type_params, params, body)) = context("operator", func_signature_rest)(s)?;
+
+            Ok((
+                s,
+                FuncLike::Operator(Operator {
+                    modifiers,
+                    return_type,
+                    name,
+                    operator_type,
+                    type_params,
+                    params,
+                    body,
+                }),
+            ))
+        }
+        FuncLikeName::Getter(name) => {
+            let (s, body) = context("getter", func_body_or_end)(s)?;
+
+            Ok((
+                s,
+                FuncLike::Getter(Getter {
+                    modifiers,
+                    return_type,
+                    name,
+                    body,
+                }),
+            ))
+        }
+    }
 }
 
-fn operator<'s, E>(s: &'s str) -> PResult<Operator, E>
+#[allow(clippy::type_complexity)]
+fn func_signature_rest<'s, E>(
+    s: &'s str,
+) -> PResult<
+    's,
+    (
+        Vec<WithMeta<'s, TypeParam<'s>>>,
+        FuncParams<'s, FuncParam<'s>>,
+        Option<FuncBody<'s>>,
+    ),
+    E,
+>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
-    context(
-        "operator",
-        tuple((
-            alt((
-                terminated(func_modifier_set, spbr),
-                success(FuncModifierSet::default()),
-            )),
-            // Return type
-            terminated(ty, opt(spbr)),
-            // Function name
-            preceded(
-                pair(tag("operator"), opt(spbr)),
-                terminated(user_def_operator, opt(spbr)),
-            ),
-            opt(terminated(type_params, opt(spbr))),
-            terminated(func_params, opt(spbr)),
-            alt((func_body.map(Some), tag(";").map(|_| None))),
-        ))
-        .map(
-            |(modifiers, return_type, operator_type, type_params, params, body)| Operator {
-                modifiers,
-                return_type,
-                operator_type,
-                type_params: type_params.unwrap_or(Vec::new()),
-                params,
-                body,
-            },
-        ),
-    )
-    .parse(s)
+    tuple((
+        alt((
+            terminated(type_params, opt(spbr)),
+            success(()).map(|_| Vec::new()),
+        )),
+        terminated(func_params, opt(spbr)),
+        func_body_or_end,
+    ))(s)
 }
 
-fn getter<'s, E>(s: &'s str) -> PResult<Getter, E>
+fn func_body_or_end<'s, E>(s: &'s str) -> PResult<'s, Option<FuncBody<'s>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
-    context(
-        "getter",
-        tuple((
-            alt((
-                terminated(func_modifier_set, spbr),
-                success(FuncModifierSet::default()),
-            )),
-            // Return type
-            terminated(ty, opt(spbr)),
-            terminated(tag("get"), spbr),
-            // Getter name
-            terminated(identifier, opt(spbr)),
-            alt((func_body.map(Some), tag(";").map(|_| None))),
-        ))
-        .map(|(modifiers, return_type, _, name, body)| Getter {
-            modifiers,
-            return_type,
-            name,
-            body,
-        }),
-    )
-    .parse(s)
+    alt((func_body.map(Some), tag(";").map(|_| None)))(s)
 }
 
-fn setter<'s, E>(s: &'s str) -> PResult<Setter, E>
+/// Parse a setter following its modifiers.
+///
+/// Unlike the other function-like declarations, a setter has no return type
+/// other than `void`.
+pub fn setter<'s, E>(s: &'s str, modifiers: FuncModifierSet) -> PResult<'s, Setter<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
     context(
         "setter",
         tuple((
-            alt((
-                terminated(func_modifier_set, spbr),
-                success(FuncModifierSet::default()),
-            )),
             // The return type of the setter must be 'void' or absent
             opt(terminated(tag("void"), opt(spbr))),
             terminated(tag("set"), spbr),
             // Setter name
             terminated(identifier, opt(spbr)),
             terminated(func_params, opt(spbr)),
-            alt((func_body.map(Some), tag(";").map(|_| None))),
+            func_body_or_end,
         ))
-        .map(|(modifiers, _, _, name, params, body)| Setter {
+        .map(|(_, _, name, params, body)| Setter {
             modifiers,
             name,
             params,
@@ -171,26 +173,7 @@ where
     .parse(s)
 }
 
-fn func_modifier_set<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<FuncModifierSet, E> {
-    let (s, modifier) = func_modifier(s)?;
-
-    let modifiers = FuncModifierSet::from_iter([modifier]);
-
-    fold_many0(
-        preceded(spbr, func_modifier),
-        move || modifiers,
-        |modifiers, modifier| modifiers.with(modifier),
-    )(s)
-}
-
-fn func_modifier<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<FuncModifier, E> {
-    alt((
-        value(FuncModifier::External, tag("external")),
-        value(FuncModifier::Static, tag("static")),
-    ))(s)
-}
-
-pub fn func_params<'s, E>(s: &'s str) -> PResult<FuncParams<FuncParam>, E>
+pub fn func_params<'s, E>(s: &'s str) -> PResult<'s, FuncParams<'s, FuncParam<'s>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -217,7 +200,7 @@ where
     .parse(s)
 }
 
-fn func_params_pos_req<'s, E>(s: &'s str) -> PResult<Vec<WithMeta<'s, FuncParam>>, E>
+fn func_params_pos_req<'s, E>(s: &'s str) -> PResult<'s, Vec<WithMeta<'s, FuncParam<'s>>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -232,7 +215,7 @@ where
     )(s)
 }
 
-fn func_params_pos_opt<'s, E>(s: &'s str) -> PResult<Vec<WithMeta<'s, FuncParam>>, E>
+fn func_params_pos_opt<'s, E>(s: &'s str) -> PResult<'s, Vec<WithMeta<'s, FuncParam<'s>>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -250,7 +233,7 @@ where
     )(s)
 }
 
-fn func_param_pos<'s, E>(s: &'s str) -> PResult<FuncParam, E>
+fn func_param_pos<'s, E>(s: &'s str) -> PResult<'s, FuncParam<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -285,7 +268,9 @@ where
     )(s)
 }
 
-fn func_params_named<'s, E>(s: &'s str) -> PResult<Vec<WithMeta<'s, MaybeRequired<FuncParam>>>, E>
+fn func_params_named<'s, E>(
+    s: &'s str,
+) -> PResult<'s, Vec<WithMeta<'s, MaybeRequired<FuncParam<'s>>>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -306,7 +291,7 @@ where
     )(s)
 }
 
-fn func_param_named<'s, E>(s: &'s str) -> PResult<MaybeRequired<FuncParam>, E>
+fn func_param_named<'s, E>(s: &'s str) -> PResult<'s, MaybeRequired<FuncParam<'s>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -346,7 +331,7 @@ where
 
 fn func_param_modifier_set<'s, E: ParseError<&'s str>>(
     s: &'s str,
-) -> PResult<FuncParamModifierSet, E> {
+) -> PResult<'s, FuncParamModifierSet, E> {
     let (s, modifier) = func_param_modifier(s)?;
 
     let modifiers = FuncParamModifierSet::from_iter([modifier]);
@@ -358,14 +343,16 @@ fn func_param_modifier_set<'s, E: ParseError<&'s str>>(
     )(s)
 }
 
-fn func_param_modifier<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<FuncParamModifier, E> {
+fn func_param_modifier<'s, E: ParseError<&'s str>>(
+    s: &'s str,
+) -> PResult<'s, FuncParamModifier, E> {
     alt((
         value(FuncParamModifier::Covariant, tag("covariant")),
         value(FuncParamModifier::Final, tag("final")),
     ))(s)
 }
 
-fn func_body<'s, E>(s: &'s str) -> PResult<FuncBody, E>
+fn func_body<'s, E>(s: &'s str) -> PResult<'s, FuncBody<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -377,7 +364,7 @@ where
     .parse(s)
 }
 
-pub fn func_body_content<'s, E>(s: &'s str) -> PResult<FuncBodyContent, E>
+pub fn func_body_content<'s, E>(s: &'s str) -> PResult<'s, FuncBodyContent<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -391,7 +378,7 @@ where
     ))(s)
 }
 
-fn func_body_modifier<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<FuncBodyModifier, E> {
+fn func_body_modifier<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<'s, FuncBodyModifier, E> {
     alt((
         value(
             FuncBodyModifier::AsyncGenerator,
@@ -405,7 +392,7 @@ fn func_body_modifier<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<FuncBod
     ))(s)
 }
 
-pub fn user_def_operator<'s, E>(s: &'s str) -> PResult<UserDefOperator, E>
+pub fn user_def_operator<'s, E>(s: &'s str) -> PResult<'s, UserDefOperator, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -449,10 +436,23 @@ where
 mod tests {
     use nom::error::VerboseError;
 
-    use crate::dart::{ty::Type, Expr, NotFuncType, TypeParam};
+    use crate::{
+        dart::{func_like::FuncModifier, Expr, NotFuncType},
+        parser::var_or_func_like::{var_or_func_like, VarOrFuncLike},
+    };
 
     use super::*;
 
+    fn func<'s, E>(s: &'s str) -> PResult<'s, Func<'s>, E>
+    where
+        E: ParseError<&'s str> + ContextError<&'s str>,
+    {
+        match var_or_func_like(s)? {
+            (s, VarOrFuncLike::FuncLike(FuncLike::Func(func))) => Ok((s, func)),
+            (_, other) => panic!("Not a function: {other:?}"),
+        }
+    }
+
     #[test]
     fn func_block_test() {
         assert_eq!(
@@ -464,18 +464,18 @@ mod tests {
                     return_type: Type::NotFunc(NotFuncType::name("void")),
                     name: "f",
                     type_params: vec![
-                        TypeParam {
+                        WithMeta::value(TypeParam {
                             name: "T",
                             extends: Some(Type::NotFunc(NotFuncType {
                                 name: "Object",
                                 type_args: Vec::new(),
                                 is_nullable: true
                             })),
-                        },
-                        TypeParam {
+                        }),
+                        WithMeta::value(TypeParam {
                             name: "U",
                             extends: None
-                        }
+                        })
                     ],
                     params: FuncParams {
                         positional_req: Vec::new(),
@@ -628,21 +628,10 @@ mod tests {
         );
     }
 
-    #[test]
-    fn func_modifier_set_test() {
-        assert_eq!(
-            func_modifier_set::<VerboseError<_>>("external static "),
-            Ok((
-                " ",
-                FuncModifierSet::from_iter([FuncModifier::External, FuncModifier::Static])
-            ))
-        );
-    }
-
     #[test]
     fn setter_test() {
         assert_eq!(
-            setter::<VerboseError<_>>("set name(String value) {} "),
+            setter::<VerboseError<_>>("set name(String value) {} ", FuncModifierSet::default()),
             Ok((
                 " ",
                 Setter {
@@ -24,7 +24,7 @@ where
     })
 }
 
-fn is_required<'s, E>(s: &'s str) -> PResult<bool, E>
+fn is_required<'s, E>(s: &'s str) -> PResult<'s, bool, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
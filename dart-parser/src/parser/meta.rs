@@ -1,15 +1,20 @@
 use nom::{
     branch::alt,
-    combinator::opt,
+    bytes::complete::tag,
+    combinator::{eof, opt, peek},
     error::{context, ContextError, ParseError},
     multi::many0,
-    sequence::terminated,
+    sequence::{preceded, terminated},
     Parser,
 };
 
-use crate::dart::{meta::Meta, WithMeta};
+use crate::dart::{meta::Meta, Comment, WithMeta};
 
-use super::{annotation, comment, common::spbr, PResult};
+use super::{
+    annotation, comment,
+    common::{br, sp, spbr},
+    PResult,
+};
 
 pub fn with_meta<'s, P, T, E>(mut p: P) -> impl FnMut(&'s str) -> PResult<WithMeta<'s, T>, E>
 where
@@ -24,16 +29,119 @@ where
     })
 }
 
-fn meta<'s, E>(s: &'s str) -> PResult<Vec<Meta>, E>
+/// Like [`with_meta`], also collecting the comments following the node
+/// on the same line.
+pub fn with_meta_trailing<'s, P, T, E>(p: P) -> impl FnMut(&'s str) -> PResult<WithMeta<'s, T>, E>
+where
+    P: Parser<&'s str, T, E>,
+    E: ParseError<&'s str> + ContextError<&'s str>,
+{
+    let mut p = with_meta(p);
+
+    move |s| {
+        let (s, node) = p(s)?;
+        let (s, trailing) = trailing_comments(s)?;
+
+        Ok((s, node.with_trailing(trailing)))
+    }
+}
+
+/// Parse the comments following a node on the same line, including
+/// the line break after them.
+///
+/// Comments followed by more code on the same line are left to lead that code.
+pub fn trailing_comments<'s, E>(s: &'s str) -> PResult<'s, Vec<Comment<'s>>, E>
+where
+    E: ParseError<&'s str> + ContextError<&'s str>,
+{
+    let mut comments = Vec::new();
+    let mut tail = s;
+    loop {
+        match preceded(opt(sp), comment)(tail) {
+            Ok((rest, comment)) => {
+                tail = rest;
+                comments.push(comment);
+                // Ends the line
+                if let Some(Comment::SingleLine(_)) = comments.last() {
+                    break;
+                }
+            }
+            Err(nom::Err::Error(_)) => break,
+            Err(err) => return Err(err),
+        }
+    }
+
+    let ends_line = match comments.last() {
+        None => false,
+        Some(Comment::SingleLine(_)) => true,
+        Some(Comment::MultiLine(_)) => {
+            peek::<_, _, E, _>(preceded(opt(sp), alt((br, eof, tag("}")))))(tail).is_ok()
+        }
+    };
+
+    if ends_line {
+        Ok((tail, comments))
+    } else {
+        Ok((s, Vec::new()))
+    }
+}
+
+/// Parse the comments at the end of a body or of a file, consuming
+/// the trailing whitespace.
+///
+/// The leading whitespace is expected to be consumed beforehand.
+pub fn dangling_comments<'s, E>(s: &'s str) -> PResult<'s, Vec<Comment<'s>>, E>
+where
+    E: ParseError<&'s str> + ContextError<&'s str>,
+{
+    many0(terminated(comment, opt(spbr)))(s)
+}
+
+fn meta<'s, E>(s: &'s str) -> PResult<'s, Vec<Meta<'s>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
     context("meta", many0(terminated(meta_item, opt(spbr))))(s)
 }
 
-fn meta_item<'s, E>(s: &'s str) -> PResult<Meta, E>
+fn meta_item<'s, E>(s: &'s str) -> PResult<'s, Meta<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
     alt((comment.map(Meta::Comment), annotation.map(Meta::Annotation)))(s)
 }
+
+#[cfg(test)]
+mod tests {
+    use nom::error::VerboseError;
+
+    use super::*;
+
+    #[test]
+    fn trailing_comments_test() {
+        assert_eq!(
+            trailing_comments::<VerboseError<_>>(" /* a */ // b\nx"),
+            Ok((
+                "x",
+                vec![Comment::MultiLine("/* a */"), Comment::SingleLine("// b\n")]
+            ))
+        );
+        assert_eq!(
+            trailing_comments::<VerboseError<_>>(" /* a */\nx"),
+            Ok(("\nx", vec![Comment::MultiLine("/* a */")]))
+        );
+    }
+
+    #[test]
+    fn trailing_comments_next_line_test() {
+        assert_eq!(
+            trailing_comments::<VerboseError<_>>("\n// a\nx"),
+            Ok(("\n// a\nx", Vec::new()))
+        );
+        // Leads the code following it on the same line
+        assert_eq!(
+            trailing_comments::<VerboseError<_>>(" /* a */ x"),
+            Ok((" /* a */ x", Vec::new()))
+        );
+    }
+}
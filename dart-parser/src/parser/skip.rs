@@ -0,0 +1,137 @@
+use memchr::{memchr, memchr2, memchr3};
+
+/// Find the end of a block given the input following its opening bracket,
+/// jumping between the significant characters with `memchr`.
+///
+/// Only the curly brackets are matched, string literals (including
+/// interpolations) and comments are skipped over.
+///
+/// Return the length of the block body including the closing bracket,
+/// or `None` if there is no closing bracket or the string interpolations
+/// are nested deeper than `max_depth`.
+pub fn block_end(s: &[u8], max_depth: usize) -> Option<usize> {
+    code_end(s, 0, max_depth)
+}
+
+fn code_end(s: &[u8], mut offset: usize, max_depth: usize) -> Option<usize> {
+    let max_depth = max_depth.checked_sub(1)?;
+    let find_quote =
+        |offset: usize| memchr2(b'\'', b'"', &s[offset..]).map_or(usize::MAX, |i| offset + i);
+
+    let mut depth = 0usize;
+    // The position of the next quote, `usize::MAX` if there is none
+    let mut next_quote = find_quote(offset);
+
+    loop {
+        let next = offset + memchr3(b'{', b'}', b'/', &s[offset..])?;
+        if next_quote < offset {
+            next_quote = find_quote(offset);
+        }
+
+        if next_quote < next {
+            offset = string_end(s, next_quote, max_depth)?;
+            continue;
+        }
+
+        offset = next + 1;
+        match s[next] {
+            b'{' => depth += 1,
+            b'}' if depth == 0 => return Some(offset),
+            b'}' => depth -= 1,
+            _ => match s.get(offset) {
+                Some(b'/') => offset = memchr(b'\n', &s[offset..]).map_or(s.len(), |i| offset + i),
+                Some(b'*') => offset = comment_multi_line_end(s, offset + 1)?,
+                _ => {}
+            },
+        }
+    }
+}
+
+/// Multi-line comments can be nested.
+fn comment_multi_line_end(s: &[u8], mut offset: usize) -> Option<usize> {
+    let mut depth = 0usize;
+
+    loop {
+        let next = offset + memchr2(b'*', b'/', &s[offset..])?;
+        offset = next + 1;
+        match (s[next], s.get(offset)) {
+            (b'*', Some(b'/')) if depth == 0 => return Some(offset + 1),
+            (b'*', Some(b'/')) => {
+                depth -= 1;
+                offset += 1;
+            }
+            (b'/', Some(b'*')) => {
+                depth += 1;
+                offset += 1;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Find the end of a string literal given the offset of its opening quote.
+fn string_end(s: &[u8], start: usize, max_depth: usize) -> Option<usize> {
+    let quote = s[start];
+    let is_raw = start > 0 && s[start - 1] == b'r' && (start < 2 || !is_ident_char(s[start - 2]));
+    let is_triple = s[start..].starts_with(&[quote; 3]);
+    let mut offset = start + if is_triple { 3 } else { 1 };
+
+    loop {
+        let next = offset
+            + if is_raw {
+                memchr(quote, s.get(offset..)?)?
+            } else {
+                memchr3(quote, b'\\', b'$', s.get(offset..)?)?
+            };
+        offset = next + 1;
+        match s[next] {
+            b'\\' => offset += 1,
+            b'$' if s.get(offset) == Some(&b'{') => offset = code_end(s, offset + 1, max_depth)?,
+            b'$' => {}
+            _ if !is_triple => return Some(offset),
+            _ if s[next..].starts_with(&[quote; 3]) => return Some(next + 3),
+            _ => {}
+        }
+    }
+}
+
+fn is_ident_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_' || c == b'$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(s: &str) -> Option<&str> {
+        block_end(&s.as_bytes()[1..], 8).map(|len| &s[..len + 1])
+    }
+
+    #[test]
+    fn block_end_test() {
+        assert_eq!(block("{ f({}); }x"), Some("{ f({}); }"));
+        assert_eq!(block("{ { }x"), None);
+        assert_eq!(
+            block(r#"{ "${ "${ "${ "${ "${ "${ "${ "${ }" }" }" }" }" }" }" }" }x"#),
+            None
+        );
+    }
+
+    #[test]
+    fn block_end_string_test() {
+        assert_eq!(
+            block(r#"{ print("}${ '}' }\"}"); }x"#),
+            Some(r#"{ print("}${ '}' }\"}"); }"#)
+        );
+        assert_eq!(block(r"{ r'\'; }x"), Some(r"{ r'\'; }"));
+        assert_eq!(block(r#"{ """ "} """; }x"#), Some(r#"{ """ "} """; }"#));
+    }
+
+    #[test]
+    fn block_end_comment_test() {
+        assert_eq!(
+            block("{ // }\n /* /* } */ } */ x / 2; }x"),
+            Some("{ // }\n /* /* } */ } */ x / 2; }")
+        );
+    }
+}
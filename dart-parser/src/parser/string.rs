@@ -8,7 +8,7 @@ use nom::{
     sequence::{pair, preceded, terminated, tuple},
 };
 
-use super::{common::skip_many0, expr::block, ty::identifier, PResult};
+use super::{common::skip_many0, depth::nested, expr::block, ty::identifier, PResult};
 
 /// Parse a single- or double-quoted single- or multiline string literal.
 ///
@@ -19,7 +19,7 @@ use super::{common::skip_many0, expr::block, ty::identifier, PResult};
 /// literals.
 ///
 /// Return the body of the string without the enclosing quotes.
-pub fn string<'s, E>(s: &'s str) -> PResult<&str, E>
+pub fn string<'s, E>(s: &'s str) -> PResult<'s, &'s str, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -108,7 +108,7 @@ where
     context("string", alt((tdq, tsq, dq, sq, rtdq, rtsq, rdq, rsq)))(s)
 }
 
-fn escape_seq<'s, E>(s: &'s str) -> PResult<&str, E>
+fn escape_seq<'s, E>(s: &'s str) -> PResult<'s, &'s str, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -127,14 +127,14 @@ where
     recognize(many_m_n(m, n, one_of("0123456789ABCDEFabcdef")))
 }
 
-fn interpolation_expr<'s, E>(s: &'s str) -> PResult<&str, E>
+fn interpolation_expr<'s, E>(s: &'s str) -> PResult<'s, &'s str, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
-    context(
+    nested(context(
         "interpolation_expr",
         recognize(preceded(tag("$"), alt((identifier, block)))),
-    )(s)
+    ))(s)
 }
 
 #[cfg(test)]
@@ -16,13 +16,14 @@ use crate::dart::{
 
 use super::{
     common::{sep_list, spbr, spbrc, SepMode},
+    depth::nested,
     maybe_required::maybe_required,
     meta::with_meta,
     type_params::type_params,
     PResult,
 };
 
-pub fn identifier<'s, E>(s: &'s str) -> PResult<&str, E>
+pub fn identifier<'s, E>(s: &'s str) -> PResult<'s, &'s str, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -60,22 +61,22 @@ where
     })(s)
 }
 
-pub fn ty<'s, E>(s: &'s str) -> PResult<Type, E>
+pub fn ty<'s, E>(s: &'s str) -> PResult<'s, Type<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
-    context(
+    nested(context(
         "ty",
         alt((
             func_type.map(Type::Func),
             not_func_type.map(Type::NotFunc),
             tuple_ty.map(Type::Tuple),
         )),
-    )(s)
+    ))(s)
 }
 
 /// Parse an identifier with type arguments and the nullability indicator (e.g. `x`, `Future<int>?`).
-pub fn not_func_type<'s, E>(s: &'s str) -> PResult<NotFuncType, E>
+pub fn not_func_type<'s, E>(s: &'s str) -> PResult<'s, NotFuncType<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -95,7 +96,7 @@ where
     .parse(s)
 }
 
-pub fn type_args<'s, E>(s: &'s str) -> PResult<Vec<Type>, E>
+pub fn type_args<'s, E>(s: &'s str) -> PResult<'s, Vec<Type<'s>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -111,7 +112,7 @@ where
     )(s)
 }
 
-fn func_type<'s, E>(s: &'s str) -> PResult<Box<FuncType>, E>
+fn func_type<'s, E>(s: &'s str) -> PResult<'s, Box<FuncType<'s>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -144,10 +145,11 @@ where
     })(s)
 }
 
+#[allow(clippy::type_complexity)]
 fn build_func_type<'s>(
     return_type: Option<NotFuncType<'s>>,
     fn_chain: Vec<(
-        Vec<TypeParam<'s>>,
+        Vec<WithMeta<'s, TypeParam<'s>>>,
         FuncParams<'s, FuncTypeParamPos<'s>, FuncTypeParamNamed<'s>>,
         bool,
     )>,
@@ -173,7 +175,7 @@ fn build_func_type<'s>(
 
 fn func_type_params<'s, E>(
     s: &'s str,
-) -> PResult<FuncParams<FuncTypeParamPos<'s>, FuncTypeParamNamed<'s>>, E>
+) -> PResult<'s, FuncParams<'s, FuncTypeParamPos<'s>, FuncTypeParamNamed<'s>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -200,7 +202,9 @@ where
     .parse(s)
 }
 
-fn func_type_params_pos_req<'s, E>(s: &'s str) -> PResult<Vec<WithMeta<FuncTypeParamPos>>, E>
+fn func_type_params_pos_req<'s, E>(
+    s: &'s str,
+) -> PResult<'s, Vec<WithMeta<'s, FuncTypeParamPos<'s>>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -212,7 +216,9 @@ where
     )(s)
 }
 
-fn func_type_params_pos_opt<'s, E>(s: &'s str) -> PResult<Vec<WithMeta<FuncTypeParamPos>>, E>
+fn func_type_params_pos_opt<'s, E>(
+    s: &'s str,
+) -> PResult<'s, Vec<WithMeta<'s, FuncTypeParamPos<'s>>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -230,7 +236,7 @@ where
     )(s)
 }
 
-fn func_type_param_pos<'s, E>(s: &'s str) -> PResult<FuncTypeParamPos, E>
+fn func_type_param_pos<'s, E>(s: &'s str) -> PResult<'s, FuncTypeParamPos<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -251,7 +257,7 @@ where
 
 fn func_type_params_named<'s, E>(
     s: &'s str,
-) -> PResult<Vec<WithMeta<MaybeRequired<FuncTypeParamNamed>>>, E>
+) -> PResult<'s, Vec<WithMeta<'s, MaybeRequired<FuncTypeParamNamed<'s>>>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -272,7 +278,7 @@ where
     )(s)
 }
 
-fn func_type_param_named<'s, E>(s: &'s str) -> PResult<FuncTypeParamNamed, E>
+fn func_type_param_named<'s, E>(s: &'s str) -> PResult<'s, FuncTypeParamNamed<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -288,7 +294,7 @@ where
     .parse(s)
 }
 
-fn tuple_ty<'s, E>(s: &'s str) -> PResult<Tuple, E>
+fn tuple_ty<'s, E>(s: &'s str) -> PResult<'s, Tuple<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -455,10 +461,10 @@ mod tests {
                 " x",
                 Box::new(FuncType {
                     return_type: Type::NotFunc(NotFuncType::name("T")),
-                    type_params: vec![TypeParam {
+                    type_params: vec![WithMeta::value(TypeParam {
                         name: "T",
                         extends: None
-                    }],
+                    })],
                     params: FuncParams {
                         positional_req: vec![WithMeta::value(FuncTypeParamPos {
                             param_type: Type::NotFunc(NotFuncType::name("T")),
@@ -7,15 +7,16 @@ use nom::{
     Parser,
 };
 
-use crate::dart::TypeParam;
+use crate::dart::{TypeParam, WithMeta};
 
 use super::{
     common::spbr,
+    meta::with_meta,
     ty::{identifier, ty},
     PResult,
 };
 
-pub fn type_params<'s, E>(s: &'s str) -> PResult<Vec<TypeParam>, E>
+pub fn type_params<'s, E>(s: &'s str) -> PResult<'s, Vec<WithMeta<'s, TypeParam<'s>>>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -24,14 +25,17 @@ where
         preceded(
             pair(tag("<"), opt(spbr)),
             cut(terminated(
-                separated_list1(pair(tag(","), opt(spbr)), terminated(type_param, opt(spbr))),
+                separated_list1(
+                    pair(tag(","), opt(spbr)),
+                    terminated(with_meta(type_param), opt(spbr)),
+                ),
                 tag(">"),
             )),
         ),
     )(s)
 }
 
-pub fn type_param<'s, E>(s: &'s str) -> PResult<TypeParam, E>
+pub fn type_param<'s, E>(s: &'s str) -> PResult<'s, TypeParam<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
@@ -44,3 +48,35 @@ where
         .map(|(name, extends)| TypeParam { name, extends }),
     )(s)
 }
+
+#[cfg(test)]
+mod tests {
+    use nom::error::VerboseError;
+
+    use crate::dart::{meta::Meta, Annotation};
+
+    use super::*;
+
+    #[test]
+    fn type_params_meta_test() {
+        assert_eq!(
+            type_params::<VerboseError<_>>("<@covariant T, U>x"),
+            Ok((
+                "x",
+                vec![
+                    WithMeta::new(
+                        vec![Meta::Annotation(Annotation::Ident("covariant"))],
+                        TypeParam {
+                            name: "T",
+                            extends: None
+                        }
+                    ),
+                    WithMeta::value(TypeParam {
+                        name: "U",
+                        extends: None
+                    }),
+                ]
+            ))
+        );
+    }
+}
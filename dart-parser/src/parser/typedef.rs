@@ -16,7 +16,7 @@ use super::{
     PResult,
 };
 
-pub fn typedef<'s, E>(s: &'s str) -> PResult<TypeDef, E>
+pub fn typedef<'s, E>(s: &'s str) -> PResult<'s, TypeDef<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
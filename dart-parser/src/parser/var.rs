@@ -1,14 +1,14 @@
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    combinator::{cut, opt, peek, success, value},
+    combinator::{cut, opt, peek, value},
     error::{context, ContextError, ParseError},
     multi::fold_many0,
-    sequence::{pair, preceded, terminated, tuple},
-    Parser,
+    sequence::{pair, preceded, terminated},
 };
 
 use crate::dart::{
+    ty::Type,
     var::{VarModifier, VarModifierSet},
     Var,
 };
@@ -16,49 +16,44 @@ use crate::dart::{
 use super::{
     common::{spbr, spbr_char},
     expr::expr,
-    ty::{identifier, ty},
     PResult,
 };
 
-pub fn var<'s, E>(s: &'s str) -> PResult<Var, E>
+/// Parse the rest of a variable declaration, once the modifiers, the type
+/// and the name are parsed.
+pub fn var_rest<'s, E>(
+    s: &'s str,
+    modifiers: VarModifierSet,
+    var_type: Option<Type<'s>>,
+    name: &'s str,
+) -> PResult<'s, Var<'s>, E>
 where
     E: ParseError<&'s str> + ContextError<&'s str>,
 {
-    context(
+    let (s, initializer) = context(
         "var",
-        tuple((
-            alt((
-                terminated(var_modifier_set, spbr),
-                success(VarModifierSet::default()),
-            )),
-            opt(terminated(tag("var"), spbr)),
-            alt((
-                // A type followed by a name
-                pair(
-                    terminated(ty, opt(spbr)).map(Some),
-                    terminated(identifier, opt(spbr)),
-                ),
-                // Just a name
-                terminated(identifier, opt(spbr)).map(|id| (None, id)),
-            )),
+        terminated(
             // An initializer
             opt(preceded(
                 pair(tag("="), opt(spbr)),
                 cut(terminated(expr, opt(spbr))),
             )),
             tag(";"),
-        ))
-        .map(|(modifiers, _, (var_type, name), initializer, _)| Var {
+        ),
+    )(s)?;
+
+    Ok((
+        s,
+        Var {
             modifiers,
             var_type,
             name,
             initializer,
-        }),
-    )
-    .parse(s)
+        },
+    ))
 }
 
-fn var_modifier_set<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<VarModifierSet, E> {
+pub fn var_modifier_set<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<'s, VarModifierSet, E> {
     let (s, modifier) = var_modifier(s)?;
 
     let modifiers = VarModifierSet::from_iter([modifier]);
@@ -70,7 +65,7 @@ fn var_modifier_set<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<VarModifi
     )(s)
 }
 
-fn var_modifier<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<VarModifier, E> {
+fn var_modifier<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<'s, VarModifier, E> {
     alt((
         value(VarModifier::External, tag("external")),
         value(VarModifier::Static, tag("static")),
@@ -85,10 +80,23 @@ fn var_modifier<'s, E: ParseError<&'s str>>(s: &'s str) -> PResult<VarModifier,
 mod tests {
     use nom::error::VerboseError;
 
-    use crate::dart::{ty::Type, Expr, NotFuncType};
+    use crate::{
+        dart::{Expr, NotFuncType},
+        parser::var_or_func_like::{var_or_func_like, VarOrFuncLike},
+    };
 
     use super::*;
 
+    fn var<'s, E>(s: &'s str) -> PResult<'s, Var<'s>, E>
+    where
+        E: ParseError<&'s str> + ContextError<&'s str>,
+    {
+        match var_or_func_like(s)? {
+            (s, VarOrFuncLike::Var(var)) => Ok((s, var)),
+            (_, VarOrFuncLike::FuncLike(func_like)) => panic!("Not a variable: {func_like:?}"),
+        }
+    }
+
     #[test]
     fn var_test() {
         assert_eq!(
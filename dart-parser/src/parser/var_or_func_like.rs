@@ -0,0 +1,210 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    combinator::{fail, opt, success},
+    error::{context, ContextError, ParseError},
+    sequence::{pair, terminated},
+    Parser,
+};
+
+use crate::dart::{
+    func_like::{FuncModifier, FuncModifierSet},
+    var::{VarModifier, VarModifierSet},
+    FuncLike, Var,
+};
+
+use super::{
+    common::spbr,
+    func_like::{func_like_name, func_like_rest, setter, FuncLikeName},
+    ty::{identifier, ty},
+    var::{var_modifier_set, var_rest},
+    PResult,
+};
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum VarOrFuncLike<'s> {
+    Var(Var<'s>),
+    FuncLike(FuncLike<'s>),
+}
+
+/// Parse a variable or a function-like declaration.
+///
+/// The modifiers, the type and the name are shared by all the alternatives,
+/// so they are parsed once and then dispatched on.
+pub fn var_or_func_like<'s, E>(s: &'s str) -> PResult<'s, VarOrFuncLike<'s>, E>
+where
+    E: ParseError<&'s str> + ContextError<&'s str>,
+{
+    context("var_or_func_like", |s| {
+        let (s, modifiers) = alt((
+            terminated(var_modifier_set, spbr),
+            success(VarModifierSet::default()),
+        ))(s)?;
+        let func_modifiers = func_modifiers(modifiers);
+
+        if let Some(func_modifiers) = func_modifiers {
+            match setter(s, func_modifiers) {
+                Ok((s, setter)) => {
+                    return Ok((s, VarOrFuncLike::FuncLike(FuncLike::Setter(setter))));
+                }
+                Err(nom::Err::Error(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        let (s, var_keyword) = opt(terminated(tag("var"), spbr))(s)?;
+        let (s, (ty, name)) = alt((
+            pair(
+                terminated(ty, opt(spbr)).map(Some),
+                terminated(func_like_name, opt(spbr)),
+            ),
+            // Just a name
+            terminated(identifier, opt(spbr)).map(|name| (None, FuncLikeName::Func(name))),
+        ))(s)?;
+
+        match (func_modifiers, var_keyword, ty, name) {
+            (_, _, ty, FuncLikeName::Func(name)) if !s.starts_with(['(', '<']) => {
+                var_rest(s, modifiers, ty, name).map(|(s, var)| (s, VarOrFuncLike::Var(var)))
+            }
+            (Some(func_modifiers), None, Some(return_type), name) => {
+                func_like_rest(s, func_modifiers, return_type, name)
+                    .map(|(s, func_like)| (s, VarOrFuncLike::FuncLike(func_like)))
+            }
+            // E.g. `var f() {}`, which can only be meant as a function
+            _ => context("func_like", fail)(s),
+        }
+    })(s)
+}
+
+/// The modifiers as function modifiers, if they are applicable to functions.
+fn func_modifiers(modifiers: VarModifierSet) -> Option<FuncModifierSet> {
+    modifiers
+        .into_iter()
+        .map(|modifier| match modifier {
+            VarModifier::External => Some(FuncModifier::External),
+            VarModifier::Static => Some(FuncModifier::Static),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::error::{VerboseError, VerboseErrorKind};
+
+    use crate::dart::{
+        func_like::{Func, FuncBody, FuncBodyContent, FuncParams, Getter},
+        ty::Type,
+        Expr, NotFuncType,
+    };
+
+    use super::*;
+
+    #[test]
+    fn func_modifiers_test() {
+        assert_eq!(
+            func_modifiers(VarModifierSet::from_iter([
+                VarModifier::External,
+                VarModifier::Static
+            ])),
+            Some(FuncModifierSet::from_iter([
+                FuncModifier::External,
+                FuncModifier::Static
+            ]))
+        );
+        assert_eq!(
+            func_modifiers(VarModifierSet::from_iter([
+                VarModifier::Static,
+                VarModifier::Final
+            ])),
+            None
+        );
+    }
+
+    #[test]
+    fn var_or_func_like_getter_test() {
+        assert_eq!(
+            var_or_func_like::<VerboseError<_>>("static int get x => 0;x"),
+            Ok((
+                "x",
+                VarOrFuncLike::FuncLike(FuncLike::Getter(Getter {
+                    modifiers: FuncModifierSet::from_iter([FuncModifier::Static]),
+                    return_type: Type::NotFunc(NotFuncType::name("int")),
+                    name: "x",
+                    body: Some(FuncBody {
+                        modifier: None,
+                        content: FuncBodyContent::Expr(Expr::Verbatim("0"))
+                    }),
+                }))
+            ))
+        );
+    }
+
+    #[test]
+    fn var_or_func_like_setter_no_void_test() {
+        assert!(matches!(
+            var_or_func_like::<VerboseError<_>>("set x(int value) {}x"),
+            Ok(("x", VarOrFuncLike::FuncLike(FuncLike::Setter(_))))
+        ));
+    }
+
+    #[test]
+    fn var_or_func_like_method_named_get_test() {
+        assert!(matches!(
+            var_or_func_like::<VerboseError<_>>("int get(String key) => 0;x"),
+            Ok(("x", VarOrFuncLike::FuncLike(FuncLike::Func(_))))
+        ));
+    }
+
+    #[test]
+    fn var_or_func_like_error_test() {
+        let Err(nom::Err::Error(err)) = var_or_func_like::<VerboseError<_>>("var f() {}x") else {
+            panic!("Expected an error");
+        };
+
+        assert_eq!(
+            err.errors[1],
+            ("() {}x", VerboseErrorKind::Context("func_like"))
+        );
+    }
+
+    #[test]
+    fn var_or_func_like_var_test() {
+        assert_eq!(
+            var_or_func_like::<VerboseError<_>>("static final get = 0;x"),
+            Ok((
+                "x",
+                VarOrFuncLike::Var(Var {
+                    modifiers: VarModifierSet::from_iter([VarModifier::Static, VarModifier::Final]),
+                    var_type: None,
+                    name: "get",
+                    initializer: Some(Expr::Verbatim("0")),
+                })
+            ))
+        );
+    }
+
+    #[test]
+    fn var_or_func_like_func_params_test() {
+        assert_eq!(
+            var_or_func_like::<VerboseError<_>>("void f() {}x"),
+            Ok((
+                "x",
+                VarOrFuncLike::FuncLike(FuncLike::Func(Func {
+                    modifiers: FuncModifierSet::default(),
+                    return_type: Type::NotFunc(NotFuncType::name("void")),
+                    name: "f",
+                    type_params: Vec::new(),
+                    params: FuncParams {
+                        positional_req: Vec::new(),
+                        extra: None,
+                    },
+                    body: Some(FuncBody {
+                        modifier: None,
+                        content: FuncBodyContent::Block("{}")
+                    }),
+                }))
+            ))
+        );
+    }
+}
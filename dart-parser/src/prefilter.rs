@@ -0,0 +1,157 @@
+use memchr::memchr_iter;
+use smallvec::SmallVec;
+
+/// A quick check whether a source may contain any of the given annotations,
+/// to avoid parsing the sources that cannot.
+///
+/// There are no false negatives, but there can be false positives
+/// (e.g. `@` in string literals and comments is not told apart).
+#[derive(Debug)]
+pub struct AnnotationPrefilter {
+    names: Vec<String>,
+}
+
+impl AnnotationPrefilter {
+    /// The names can be given with or without the import prefix
+    /// (e.g. `freezed` or `f.freezed`). A name without a prefix matches
+    /// the annotation regardless of the prefix.
+    pub fn new<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            names: names.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn may_contain(&self, source: &str) -> bool {
+        let s = source.as_bytes();
+
+        memchr_iter(b'@', s).any(|at| {
+            let segments = annotation_segments(s, at + 1);
+            self.names.iter().any(|name| {
+                let len = name.split('.').count();
+                segments.windows(len).any(|window| {
+                    window
+                        .iter()
+                        .zip(name.split('.'))
+                        .all(|(segment, name)| *segment == name.as_bytes())
+                })
+            })
+        })
+    }
+}
+
+/// Read the segments of the identifier following an `@`, skipping the
+/// whitespace and comments: the prefix, the class and the constructor name
+/// (e.g. `p.Foo.named`), in any combination.
+///
+/// Any segment may be the name of the annotation, so a name without a prefix
+/// is matched against every one of them.
+fn annotation_segments(s: &[u8], mut offset: usize) -> SmallVec<[&[u8]; 3]> {
+    let mut segments = SmallVec::new();
+
+    while segments.len() < segments.inline_size() {
+        offset = skip_trivia(s, offset);
+        let len = s[offset..]
+            .iter()
+            .position(|&c| !is_ident_char(c))
+            .unwrap_or(s.len() - offset);
+        if len == 0 {
+            break;
+        }
+        segments.push(&s[offset..offset + len]);
+        offset = skip_trivia(s, offset + len);
+
+        if s.get(offset) == Some(&b'.') {
+            offset += 1;
+        } else {
+            break;
+        }
+    }
+
+    segments
+}
+
+/// Skip whitespace and comments.
+fn skip_trivia(s: &[u8], mut offset: usize) -> usize {
+    loop {
+        let rest = &s[offset..];
+        if rest.first().is_some_and(u8::is_ascii_whitespace) {
+            offset += 1;
+        } else if rest.starts_with(b"//") {
+            offset += rest
+                .iter()
+                .position(|&c| matches!(c, b'\r' | b'\n'))
+                .unwrap_or(rest.len());
+        } else if rest.starts_with(b"/*") {
+            offset += comment_multi_line_len(rest);
+        } else {
+            return offset;
+        }
+    }
+}
+
+/// Multi-line comments can be nested.
+fn comment_multi_line_len(s: &[u8]) -> usize {
+    let mut depth = 0;
+    let mut offset = 0;
+
+    while offset < s.len() {
+        if s[offset..].starts_with(b"/*") {
+            depth += 1;
+            offset += 2;
+        } else if s[offset..].starts_with(b"*/") {
+            depth -= 1;
+            offset += 2;
+            if depth == 0 {
+                return offset;
+            }
+        } else {
+            offset += 1;
+        }
+    }
+
+    s.len()
+}
+
+fn is_ident_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_' || c == b'$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefilter_test() {
+        let prefilter = AnnotationPrefilter::new(["JsonSerializable", "freezed"]);
+
+        assert!(prefilter.may_contain("@JsonSerializable()\nclass A {}"));
+        assert!(prefilter.may_contain("@json.JsonSerializable()\nclass A {}"));
+        assert!(prefilter.may_contain("@ f /* prefix */ . freezed\nclass A {}"));
+        assert!(prefilter.may_contain("@\r// c\rfreezed\rclass A {}"));
+        assert!(!prefilter.may_contain("@JsonKey()\nclass A {}"));
+        assert!(!prefilter.may_contain("@freezedLike\nclass A {}"));
+        assert!(!prefilter.may_contain("class JsonSerializable {}"));
+    }
+
+    #[test]
+    fn prefilter_prefixed_name_test() {
+        let prefilter = AnnotationPrefilter::new(["f.freezed"]);
+
+        assert!(prefilter.may_contain("@f.freezed\nclass A {}"));
+        assert!(!prefilter.may_contain("@freezed\nclass A {}"));
+        assert!(prefilter.may_contain("@f.freezed.named()\nclass A {}"));
+    }
+
+    #[test]
+    fn prefilter_named_constructor_test() {
+        let prefilter = AnnotationPrefilter::new(["Foo"]);
+
+        assert!(prefilter.may_contain("@Foo.named()\nclass A {}"));
+        assert!(prefilter.may_contain("@p.Foo.named()\nclass A {}"));
+        assert!(!prefilter.may_contain("@p.Bar.named()\nclass A {}"));
+    }
+}
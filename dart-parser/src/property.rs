@@ -0,0 +1,290 @@
+use crate::{
+    annotations,
+    dart::{
+        class::ClassMember,
+        extension::ExtensionMember,
+        func_like::{FuncLike, FuncModifier, Getter, Setter},
+        meta::Meta,
+        ty::Type,
+        var::VarModifier,
+        Annotation, Class, EnumTy, Extension, Var,
+    },
+    Annotated, WithMeta,
+};
+
+/// A field, or a getter and/or a setter with the same name.
+#[derive(Debug)]
+pub struct Property<'a, 's> {
+    pub name: &'s str,
+    pub kind: PropertyKind<'a, 's>,
+}
+
+#[derive(Debug)]
+pub enum PropertyKind<'a, 's> {
+    Field(Annotated<'a, 's, Var<'s>>),
+    /// At least one of them is present.
+    Accessors {
+        getter: Option<Annotated<'a, 's, Getter<'s>>>,
+        setter: Option<Annotated<'a, 's, Setter<'s>>>,
+    },
+}
+
+impl<'a, 's> Property<'a, 's> {
+    pub fn is_readable(&self) -> bool {
+        match &self.kind {
+            PropertyKind::Field(_) => true,
+            PropertyKind::Accessors { getter, .. } => getter.is_some(),
+        }
+    }
+
+    /// A field is writable unless it's `final` or `const`, a `late final`
+    /// field without an initializer can be assigned once.
+    pub fn is_writable(&self) -> bool {
+        match &self.kind {
+            PropertyKind::Field(field) => field.node.has_setter(),
+            PropertyKind::Accessors { setter, .. } => setter.is_some(),
+        }
+    }
+
+    pub fn is_static(&self) -> bool {
+        match &self.kind {
+            PropertyKind::Field(field) => field.node.modifiers.contains(VarModifier::Static),
+            PropertyKind::Accessors { getter, setter } => {
+                getter.is_some_and(|getter| getter.node.modifiers.contains(FuncModifier::Static))
+                    || setter
+                        .is_some_and(|setter| setter.node.modifiers.contains(FuncModifier::Static))
+            }
+        }
+    }
+
+    /// The declared type: the type of the field, the return type of the
+    /// getter, or else the parameter type of the setter. `None` if it's
+    /// left to inference.
+    pub fn ty(&self) -> Option<&'a Type<'s>> {
+        match &self.kind {
+            PropertyKind::Field(field) => field.node.var_type.as_ref(),
+            PropertyKind::Accessors {
+                getter: Some(getter),
+                ..
+            } => Some(&getter.node.return_type),
+            PropertyKind::Accessors {
+                setter: Some(setter),
+                ..
+            } => setter
+                .node
+                .params
+                .positional_req
+                .first()
+                .and_then(|param| param.as_ref().param_type.as_ref()),
+            PropertyKind::Accessors { .. } => None,
+        }
+    }
+
+    /// The annotations of the field, or of the getter and the setter.
+    pub fn annotations(&self) -> impl Iterator<Item = &'a Annotation<'s>> {
+        let (first, second): (&'a [Meta<'s>], &'a [Meta<'s>]) = match &self.kind {
+            PropertyKind::Field(field) => (field.meta, &[]),
+            PropertyKind::Accessors { getter, setter } => (
+                getter.map_or(&[], |getter| getter.meta),
+                setter.map_or(&[], |setter| setter.meta),
+            ),
+        };
+
+        annotations(first).chain(annotations(second))
+    }
+}
+
+impl<'s> Class<'s> {
+    /// The fields and the getter/setter pairs, in declaration order
+    /// (of the first accessor of a pair).
+    pub fn properties(&self) -> Vec<Property<'_, 's>> {
+        properties(self.body.iter().filter_map(|member| {
+            let part = match member.as_ref() {
+                ClassMember::Var(var) => Part::Var(var),
+                ClassMember::FuncLike(func_like) => Part::FuncLike(func_like),
+                ClassMember::Constructor(_) => return None,
+            };
+            Some((member, part))
+        }))
+    }
+}
+
+impl<'s> EnumTy<'s> {
+    /// See [`Class::properties`].
+    pub fn properties(&self) -> Vec<Property<'_, 's>> {
+        properties(self.members.iter().filter_map(|member| {
+            let part = match member.as_ref() {
+                ClassMember::Var(var) => Part::Var(var),
+                ClassMember::FuncLike(func_like) => Part::FuncLike(func_like),
+                ClassMember::Constructor(_) => return None,
+            };
+            Some((member, part))
+        }))
+    }
+}
+
+impl<'s> Extension<'s> {
+    /// See [`Class::properties`].
+    pub fn properties(&self) -> Vec<Property<'_, 's>> {
+        properties(self.body.iter().map(|member| {
+            let part = match member.as_ref() {
+                ExtensionMember::Var(var) => Part::Var(var),
+                ExtensionMember::FuncLike(func_like) => Part::FuncLike(func_like),
+            };
+            (member, part)
+        }))
+    }
+}
+
+enum Part<'a, 's> {
+    Var(&'a Var<'s>),
+    FuncLike(&'a FuncLike<'s>),
+}
+
+fn properties<'a, 's: 'a, T: 'a>(
+    members: impl IntoIterator<Item = (&'a WithMeta<'s, T>, Part<'a, 's>)>,
+) -> Vec<Property<'a, 's>> {
+    let mut properties: Vec<Property<'a, 's>> = Vec::new();
+
+    for (member, part) in members {
+        let meta: &'a [Meta<'s>] = &member.meta;
+        match part {
+            Part::Var(var) => properties.push(Property {
+                name: var.name,
+                kind: PropertyKind::Field(Annotated { meta, node: var }),
+            }),
+            Part::FuncLike(FuncLike::Getter(getter)) => {
+                let getter = Annotated { meta, node: getter };
+                match accessors(&mut properties, getter.node.name) {
+                    Some((slot @ None, _)) => *slot = Some(getter),
+                    _ => properties.push(Property {
+                        name: getter.node.name,
+                        kind: PropertyKind::Accessors {
+                            getter: Some(getter),
+                            setter: None,
+                        },
+                    }),
+                }
+            }
+            Part::FuncLike(FuncLike::Setter(setter)) => {
+                let setter = Annotated { meta, node: setter };
+                match accessors(&mut properties, setter.node.name) {
+                    Some((_, slot @ None)) => *slot = Some(setter),
+                    _ => properties.push(Property {
+                        name: setter.node.name,
+                        kind: PropertyKind::Accessors {
+                            getter: None,
+                            setter: Some(setter),
+                        },
+                    }),
+                }
+            }
+            Part::FuncLike(_) => {}
+        }
+    }
+
+    properties
+}
+
+type AccessorSlots<'b, 'a, 's> = (
+    &'b mut Option<Annotated<'a, 's, Getter<'s>>>,
+    &'b mut Option<Annotated<'a, 's, Setter<'s>>>,
+);
+
+/// The getter and setter of an already seen property.
+fn accessors<'b, 'a, 's>(
+    properties: &'b mut [Property<'a, 's>],
+    name: &str,
+) -> Option<AccessorSlots<'b, 'a, 's>> {
+    properties
+        .iter_mut()
+        .find_map(|property| match &mut property.kind {
+            PropertyKind::Accessors { getter, setter } if property.name == name => {
+                Some((getter, setter))
+            }
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{classes, Dart, ParsedSource};
+
+    #[test]
+    fn class_properties_test() {
+        let source = ParsedSource::parse(
+            "class User {\n\
+            \x20 static const table = 'users';\n\
+            \x20 final int id;\n\
+            \x20 late final String token;\n\
+            \x20 String? _name;\n\
+            \x20 User(this.id);\n\
+            \x20 @JsonKey(name: 'name')\n\
+            \x20 String get name => _name ?? '';\n\
+            \x20 void greet() {}\n\
+            \x20 @deprecated\n\
+            \x20 set name(String value) => _name = value;\n\
+            \x20 bool get isAdmin => id == 0;\n\
+            \x20 set password(String value) {}\n\
+            }\n"
+            .to_owned(),
+        )
+        .unwrap();
+        let user = classes(source.ast()).next().unwrap().node;
+        let properties = user.properties();
+
+        let summary = properties
+            .iter()
+            .map(|property| {
+                (
+                    property.name,
+                    property.is_readable(),
+                    property.is_writable(),
+                    property.is_static(),
+                    property.ty().map(ToString::to_string),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("table", true, false, true, None),
+                ("id", true, false, false, Some("int".to_owned())),
+                ("token", true, true, false, Some("String".to_owned())),
+                ("_name", true, true, false, Some("String?".to_owned())),
+                ("name", true, true, false, Some("String".to_owned())),
+                ("isAdmin", true, false, false, Some("bool".to_owned())),
+                ("password", false, true, false, Some("String".to_owned())),
+            ]
+        );
+        assert_eq!(
+            properties[4]
+                .annotations()
+                .map(Annotation::name)
+                .collect::<Vec<_>>(),
+            vec!["JsonKey", "deprecated"]
+        );
+    }
+
+    #[test]
+    fn extension_properties_test() {
+        let source = ParsedSource::parse(
+            "extension Names on List<String> {\n\
+            \x20 static const separator = ', ';\n\
+            \x20 String get joined => join(separator);\n\
+            }\n"
+            .to_owned(),
+        )
+        .unwrap();
+        let Dart::Extension(extension) = source.ast()[0].as_ref() else {
+            panic!("expected an extension");
+        };
+
+        let properties = extension.properties();
+        assert_eq!(properties.len(), 2);
+        assert!(properties[0].is_static());
+        assert!(!properties[1].is_static());
+        assert!(!properties[1].is_writable());
+    }
+}
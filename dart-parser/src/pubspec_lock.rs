@@ -0,0 +1,326 @@
+use std::path::{Path, PathBuf};
+
+use serde_yaml::Value;
+
+use crate::library::{normalize, resolve_relative};
+
+/// The dependencies of a Dart package pinned by `pub get` in `pubspec.lock`.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct PubspecLock {
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: Option<String>,
+    /// E.g. `direct main`, `direct dev` or `transitive`.
+    pub dependency: Option<String>,
+    pub source: PackageSource,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum PackageSource {
+    /// A package from a package repository, e.g. `https://pub.dev`.
+    Hosted { url: String },
+    Git {
+        url: String,
+        resolved_ref: String,
+        /// The path of the package inside the repository.
+        path: String,
+    },
+    /// A local package, with the path resolved against the lock file.
+    Path(PathBuf),
+    /// A package that comes with an SDK, e.g. `flutter`.
+    Sdk(String),
+}
+
+impl PubspecLock {
+    /// Parse a `pubspec.lock` file, resolving the relative paths
+    /// against the directory of `path`.
+    pub fn parse(yaml: &str, path: &Path) -> Result<Self, String> {
+        let invalid = |msg: &str| format!("Invalid lock file at path {path:?}: {msg}");
+
+        let lock: Value = serde_yaml::from_str(yaml).map_err(|e| invalid(&e.to_string()))?;
+        let entries = match &lock["packages"] {
+            Value::Mapping(entries) => entries,
+            // An empty map is written as `packages: {}`, or not at all
+            Value::Null => return Ok(Self::default()),
+            _ => return Err(invalid("`packages` is not a map")),
+        };
+
+        let packages = entries
+            .iter()
+            .map(|(name, entry)| {
+                let name = name
+                    .as_str()
+                    .ok_or_else(|| invalid("a package name is not a string"))?;
+                let invalid_package = |msg: &str| invalid(&format!("package {name:?}: {msg}"));
+                let str_field = |value: &Value, key: &str| {
+                    value[key]
+                        .as_str()
+                        .map(ToOwned::to_owned)
+                        .ok_or_else(|| invalid_package(&format!("missing `{key}`")))
+                };
+
+                let description = &entry["description"];
+                let source = match entry["source"].as_str() {
+                    Some("hosted") => PackageSource::Hosted {
+                        url: str_field(description, "url")?,
+                    },
+                    Some("git") => PackageSource::Git {
+                        url: str_field(description, "url")?,
+                        resolved_ref: str_field(description, "resolved-ref")?,
+                        path: str_field(description, "path").unwrap_or_else(|_| ".".to_owned()),
+                    },
+                    Some("path") => {
+                        let package_path = str_field(description, "path")?;
+                        let is_relative = description["relative"].as_bool().unwrap_or(false);
+                        let package_path = if is_relative {
+                            resolve_relative(path, &package_path).ok_or_else(|| {
+                                invalid_package(&format!("unsupported path {package_path:?}"))
+                            })?
+                        } else {
+                            normalize(Path::new(&package_path))
+                        };
+                        PackageSource::Path(package_path)
+                    }
+                    Some("sdk") => PackageSource::Sdk(
+                        description
+                            .as_str()
+                            .ok_or_else(|| invalid_package("missing the SDK name"))?
+                            .to_owned(),
+                    ),
+                    Some(source) => {
+                        return Err(invalid_package(&format!("unknown source {source:?}")))
+                    }
+                    None => return Err(invalid_package("missing `source`")),
+                };
+
+                Ok(LockedPackage {
+                    name: name.to_owned(),
+                    version: entry["version"].as_str().map(ToOwned::to_owned),
+                    dependency: entry["dependency"].as_str().map(ToOwned::to_owned),
+                    source,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self { packages })
+    }
+}
+
+impl LockedPackage {
+    /// The root directory of the package, given the pub cache directory.
+    ///
+    /// No network access is involved: `exists` tells whether a candidate
+    /// directory is present, so a package that hasn't been downloaded
+    /// (or comes with an SDK) has no root.
+    pub fn root(&self, pub_cache: &Path, exists: impl Fn(&Path) -> bool) -> Option<PathBuf> {
+        let candidates = match &self.source {
+            PackageSource::Hosted { url } => {
+                let dir_name = format!("{}-{}", self.name, self.version.as_deref()?);
+                hosted_dir_names(url)
+                    .into_iter()
+                    .map(|host| pub_cache.join("hosted").join(host).join(&dir_name))
+                    .collect()
+            }
+            PackageSource::Git {
+                url,
+                resolved_ref,
+                path,
+            } => {
+                let repo_name = url
+                    .trim_end_matches('/')
+                    .rsplit('/')
+                    .next()
+                    .map(|name| name.trim_end_matches(".git"))?;
+                vec![normalize(
+                    &pub_cache
+                        .join("git")
+                        .join(format!("{repo_name}-{resolved_ref}"))
+                        .join(path),
+                )]
+            }
+            PackageSource::Path(path) => vec![path.clone()],
+            PackageSource::Sdk(_) => Vec::new(),
+        };
+
+        candidates.into_iter().find(|dir| exists(dir))
+    }
+}
+
+/// The pub cache directory: `PUB_CACHE` if set, otherwise the platform
+/// default under the home directory.
+pub fn pub_cache_dir(var: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    if let Some(dir) = var("PUB_CACHE").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+
+    if cfg!(windows) {
+        var("LOCALAPPDATA").map(|dir| Path::new(&dir).join("Pub").join("Cache"))
+    } else {
+        var("HOME").map(|dir| Path::new(&dir).join(".pub-cache"))
+    }
+}
+
+/// The directory names of a package repository in `<pub cache>/hosted`,
+/// escaped the way pub does it (e.g. `localhost%583000` for a port).
+///
+/// `pub.dev` used to be `pub.dartlang.org`, older caches use either name.
+fn hosted_dir_names(url: &str) -> Vec<String> {
+    let url = url.trim_end_matches('/');
+    let (scheme, rest) = url.split_once("://").unwrap_or(("https", url));
+    let localhost_rest = ["localhost", "127.0.0.1", "[::1]"]
+        .iter()
+        .find_map(|host| rest.strip_prefix(host));
+    let url = match (scheme, localhost_rest) {
+        (_, Some(rest)) => format!("localhost{rest}"),
+        ("https", None) => rest.to_owned(),
+        _ => url.to_owned(),
+    };
+
+    let escaped = url
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '\\' | '/' | '|' | '?' | '*' | '%' => format!("%{}", c as u32),
+            c => c.to_string(),
+        })
+        .collect::<String>();
+
+    match escaped.as_str() {
+        "pub.dev" | "pub.dartlang.org" => vec!["pub.dev".to_owned(), "pub.dartlang.org".to_owned()],
+        _ => vec![escaped],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBSPEC_LOCK: &str = r#"# Generated by pub
+# See https://dart.dev/tools/pub/glossary#lockfile
+packages:
+  collection:
+    dependency: transitive
+    description:
+      name: collection
+      sha256: "ee67cb0715911d28db6bf4af1026078bd6f0128b07a5f66fb2ed94ec6783c09a"
+      url: "https://pub.dartlang.org"
+    source: hosted
+    version: "1.18.0"
+  equatable:
+    dependency: "direct main"
+    description:
+      path: "packages/equatable"
+      ref: main
+      resolved-ref: "3a7a5e1b4c"
+      url: "https://github.com/felangel/equatable.git"
+    source: git
+    version: "2.0.5"
+  flutter:
+    dependency: "direct main"
+    description: flutter
+    source: sdk
+    version: "0.0.0"
+  shared:
+    dependency: "direct main"
+    description:
+      path: "../shared"
+      relative: true
+    source: path
+    version: "1.0.0"
+  private:
+    dependency: "direct main"
+    description:
+      name: private
+      sha256: "0000"
+      url: "http://localhost:8080"
+    source: hosted
+    version: "0.1.0"
+sdks:
+  dart: ">=3.0.0 <4.0.0"
+"#;
+
+    fn lock() -> PubspecLock {
+        PubspecLock::parse(PUBSPEC_LOCK, Path::new("/work/app/pubspec.lock")).unwrap()
+    }
+
+    fn root(lock: &PubspecLock, name: &str) -> Option<PathBuf> {
+        let package = lock.packages.iter().find(|package| package.name == name)?;
+
+        package.root(Path::new("/home/user/.pub-cache"), |path| {
+            !path.starts_with("/home/user/.pub-cache/hosted/pub.dartlang.org")
+        })
+    }
+
+    #[test]
+    fn pubspec_lock_test() {
+        let lock = lock();
+
+        assert_eq!(lock.packages.len(), 5);
+        assert_eq!(
+            lock.packages[0],
+            LockedPackage {
+                name: "collection".to_owned(),
+                version: Some("1.18.0".to_owned()),
+                dependency: Some("transitive".to_owned()),
+                source: PackageSource::Hosted {
+                    url: "https://pub.dartlang.org".to_owned()
+                },
+            }
+        );
+        assert_eq!(
+            lock.packages[2].source,
+            PackageSource::Sdk("flutter".to_owned())
+        );
+    }
+
+    #[test]
+    fn pubspec_lock_root_test() {
+        let lock = lock();
+
+        assert_eq!(
+            root(&lock, "collection"),
+            Some(PathBuf::from(
+                "/home/user/.pub-cache/hosted/pub.dev/collection-1.18.0"
+            ))
+        );
+        assert_eq!(
+            root(&lock, "equatable"),
+            Some(PathBuf::from(
+                "/home/user/.pub-cache/git/equatable-3a7a5e1b4c/packages/equatable"
+            ))
+        );
+        assert_eq!(root(&lock, "flutter"), None);
+        assert_eq!(root(&lock, "shared"), Some(PathBuf::from("/work/shared")));
+        assert_eq!(
+            root(&lock, "private"),
+            Some(PathBuf::from(
+                "/home/user/.pub-cache/hosted/localhost%588080/private-0.1.0"
+            ))
+        );
+    }
+
+    #[test]
+    fn pub_cache_dir_test() {
+        let var = |vars: &'static [(&str, &str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        assert_eq!(
+            pub_cache_dir(var(&[("PUB_CACHE", "/cache"), ("HOME", "/home/user")])),
+            Some(PathBuf::from("/cache"))
+        );
+        if !cfg!(windows) {
+            assert_eq!(
+                pub_cache_dir(var(&[("HOME", "/home/user")])),
+                Some(PathBuf::from("/home/user/.pub-cache"))
+            );
+        }
+    }
+}
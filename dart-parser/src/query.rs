@@ -0,0 +1,357 @@
+//! Lookups over the parsed declarations, such as the classes with
+//! an annotation or the fields of a class.
+//!
+//! The names can be given with or without an import prefix: `JsonKey`
+//! matches both `@JsonKey()` and `@json.JsonKey()`, while `json.JsonKey`
+//! matches only the latter.
+
+use crate::{
+    dart::{
+        class::{ClassMember, Constructor},
+        func_like::{Func, FuncLike, FuncModifier},
+        meta::Meta,
+        ty::Type,
+        var::VarModifier,
+        Annotation, Class, EnumTy, Var,
+    },
+    Dart, WithMeta,
+};
+
+/// A declaration with its metadata.
+#[derive(Debug)]
+pub struct Annotated<'a, 's, T> {
+    pub meta: &'a [Meta<'s>],
+    pub node: &'a T,
+}
+
+impl<'a, 's, T> Clone for Annotated<'a, 's, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, 's, T> Copy for Annotated<'a, 's, T> {}
+
+impl<'a, 's, T> Annotated<'a, 's, T> {
+    pub fn annotations(&self) -> impl Iterator<Item = &'a Annotation<'s>> {
+        annotations(self.meta)
+    }
+
+    /// The first annotation with the name, e.g. `JsonKey`.
+    pub fn annotation(&self, name: &str) -> Option<&'a Annotation<'s>> {
+        self.annotations()
+            .find(|annotation| is_name(annotation.name(), name))
+    }
+
+    pub fn has_annotation(&self, name: &str) -> bool {
+        self.annotation(name).is_some()
+    }
+}
+
+impl<'s> Class<'s> {
+    /// The instance fields.
+    pub fn fields(&self) -> impl Iterator<Item = Annotated<'_, 's, Var<'s>>> {
+        fields(&self.body)
+    }
+
+    pub fn constructors(&self) -> impl Iterator<Item = Annotated<'_, 's, Constructor<'s>>> {
+        constructors(&self.body)
+    }
+
+    /// A constructor by name, `None` for the unnamed constructor
+    /// (e.g. `fromJson` for `User.fromJson`).
+    pub fn constructor(&self, name: Option<&str>) -> Option<Annotated<'_, 's, Constructor<'s>>> {
+        self.constructors()
+            .find(|constructor| constructor.node.short_name() == name)
+    }
+
+    /// The instance methods, getters, setters and operators.
+    pub fn methods(&self) -> impl Iterator<Item = Annotated<'_, 's, FuncLike<'s>>> {
+        methods(&self.body)
+    }
+}
+
+impl<'s> EnumTy<'s> {
+    /// The instance fields.
+    pub fn fields(&self) -> impl Iterator<Item = Annotated<'_, 's, Var<'s>>> {
+        fields(&self.members)
+    }
+
+    pub fn constructors(&self) -> impl Iterator<Item = Annotated<'_, 's, Constructor<'s>>> {
+        constructors(&self.members)
+    }
+
+    /// The instance methods, getters, setters and operators.
+    pub fn methods(&self) -> impl Iterator<Item = Annotated<'_, 's, FuncLike<'s>>> {
+        methods(&self.members)
+    }
+
+    pub fn implements(&self, name: &str) -> bool {
+        self.implements.iter().any(|ty| is_name(ty.name, name))
+    }
+}
+
+pub fn annotations<'a, 's>(meta: &'a [Meta<'s>]) -> impl Iterator<Item = &'a Annotation<'s>> {
+    meta.iter().filter_map(|meta| match meta {
+        Meta::Annotation(annotation) => Some(annotation),
+        Meta::Comment(_) => None,
+    })
+}
+
+pub fn classes<'a, 's: 'a>(
+    items: impl IntoIterator<Item = &'a WithMeta<'s, Dart<'s>>>,
+) -> impl Iterator<Item = Annotated<'a, 's, Class<'s>>> {
+    items.into_iter().filter_map(|item| match item.as_ref() {
+        Dart::Class(class) => Some(Annotated {
+            meta: &item.meta,
+            node: class,
+        }),
+        _ => None,
+    })
+}
+
+/// The classes annotated with `@<name>` or `@<name>(...)`.
+pub fn classes_annotated<'a, 's: 'a>(
+    items: impl IntoIterator<Item = &'a WithMeta<'s, Dart<'s>>>,
+    name: &'a str,
+) -> impl Iterator<Item = Annotated<'a, 's, Class<'s>>> {
+    classes(items).filter(move |class| class.has_annotation(name))
+}
+
+pub fn enums<'a, 's: 'a>(
+    items: impl IntoIterator<Item = &'a WithMeta<'s, Dart<'s>>>,
+) -> impl Iterator<Item = Annotated<'a, 's, EnumTy<'s>>> {
+    items.into_iter().filter_map(|item| match item.as_ref() {
+        Dart::Enum(enum_ty) => Some(Annotated {
+            meta: &item.meta,
+            node: enum_ty,
+        }),
+        _ => None,
+    })
+}
+
+/// The enums that implement the interface directly.
+pub fn enums_implementing<'a, 's: 'a>(
+    items: impl IntoIterator<Item = &'a WithMeta<'s, Dart<'s>>>,
+    interface: &'a str,
+) -> impl Iterator<Item = Annotated<'a, 's, EnumTy<'s>>> {
+    enums(items).filter(move |enum_ty| enum_ty.node.implements(interface))
+}
+
+/// The top-level functions, without the getters and setters.
+pub fn functions<'a, 's: 'a>(
+    items: impl IntoIterator<Item = &'a WithMeta<'s, Dart<'s>>>,
+) -> impl Iterator<Item = Annotated<'a, 's, Func<'s>>> {
+    items.into_iter().filter_map(|item| match item.as_ref() {
+        Dart::FuncLike(FuncLike::Func(func)) => Some(Annotated {
+            meta: &item.meta,
+            node: func,
+        }),
+        _ => None,
+    })
+}
+
+/// The top-level functions with the return type of the name, e.g. `Future`.
+pub fn functions_returning<'a, 's: 'a>(
+    items: impl IntoIterator<Item = &'a WithMeta<'s, Dart<'s>>>,
+    type_name: &'a str,
+) -> impl Iterator<Item = Annotated<'a, 's, Func<'s>>> {
+    functions(items).filter(move |func| match &func.node.return_type {
+        Type::NotFunc(ty) => is_name(ty.name, type_name),
+        _ => false,
+    })
+}
+
+fn fields<'a, 's>(
+    body: &'a [WithMeta<'s, ClassMember<'s>>],
+) -> impl Iterator<Item = Annotated<'a, 's, Var<'s>>> {
+    body.iter().filter_map(|member| match member.as_ref() {
+        ClassMember::Var(var) if !var.modifiers.contains(VarModifier::Static) => Some(Annotated {
+            meta: &member.meta,
+            node: var,
+        }),
+        _ => None,
+    })
+}
+
+fn constructors<'a, 's>(
+    body: &'a [WithMeta<'s, ClassMember<'s>>],
+) -> impl Iterator<Item = Annotated<'a, 's, Constructor<'s>>> {
+    body.iter().filter_map(|member| match member.as_ref() {
+        ClassMember::Constructor(constructor) => Some(Annotated {
+            meta: &member.meta,
+            node: constructor,
+        }),
+        _ => None,
+    })
+}
+
+fn methods<'a, 's>(
+    body: &'a [WithMeta<'s, ClassMember<'s>>],
+) -> impl Iterator<Item = Annotated<'a, 's, FuncLike<'s>>> {
+    body.iter().filter_map(|member| match member.as_ref() {
+        ClassMember::FuncLike(func_like) if !is_static(func_like) => Some(Annotated {
+            meta: &member.meta,
+            node: func_like,
+        }),
+        _ => None,
+    })
+}
+
+fn is_static(func_like: &FuncLike) -> bool {
+    let modifiers = match func_like {
+        FuncLike::Func(func) => func.modifiers,
+        FuncLike::Operator(operator) => operator.modifiers,
+        FuncLike::Getter(getter) => getter.modifiers,
+        FuncLike::Setter(setter) => setter.modifiers,
+    };
+
+    modifiers.contains(FuncModifier::Static)
+}
+
+/// Whether a name as written (e.g. `json.JsonKey`) matches the name
+/// of a query, which may omit the prefix.
+fn is_name(actual: &str, name: &str) -> bool {
+    actual == name
+        || !name.contains('.')
+            && actual
+                .rsplit_once('.')
+                .is_some_and(|(_, actual)| actual == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dart::Expr, ParsedSource};
+
+    const SOURCE: &str = "import 'package:json_annotation/json_annotation.dart' as json;\n\
+        \n\
+        @json.JsonSerializable(fieldRename: FieldRename.snake)\n\
+        class User {\n\
+        \x20 static const table = 'users';\n\
+        \x20 @JsonKey(name: 'id')\n\
+        \x20 final int id;\n\
+        \x20 final List<String>? tags;\n\
+        \x20 User(this.id, this.tags);\n\
+        \x20 factory User.fromJson(Map<String, dynamic> json) => _$UserFromJson(json);\n\
+        \x20 Map<String, dynamic> toJson() => _$UserToJson(this);\n\
+        \x20 static User empty() => User(0, null);\n\
+        }\n\
+        \n\
+        class Plain {}\n\
+        \n\
+        enum Role implements Comparable<Role> {\n\
+        \x20 admin, user;\n\
+        \x20 final int level = 0;\n\
+        }\n\
+        \n\
+        enum Color { red }\n\
+        \n\
+        Future<User> fetchUser() async => User(0, null);\n\
+        async.Future<void> save(User user) async {}\n\
+        int count() => 0;\n";
+
+    #[test]
+    fn query_classes_test() {
+        let source = ParsedSource::parse(SOURCE.to_owned()).unwrap();
+
+        let classes = classes_annotated(source.ast(), "JsonSerializable").collect::<Vec<_>>();
+        assert_eq!(
+            classes
+                .iter()
+                .map(|class| class.node.name)
+                .collect::<Vec<_>>(),
+            vec!["User"]
+        );
+        assert_eq!(
+            classes_annotated(source.ast(), "json.JsonSerializable").count(),
+            1
+        );
+        assert_eq!(
+            classes_annotated(source.ast(), "other.JsonSerializable").count(),
+            0
+        );
+
+        let annotation = classes[0].annotation("JsonSerializable").unwrap();
+        assert_eq!(annotation.name(), "json.JsonSerializable");
+        assert_eq!(
+            annotation.arg("fieldRename").map(|arg| &arg.value),
+            Some(&Expr::Ident("FieldRename.snake"))
+        );
+    }
+
+    #[test]
+    fn query_named_constructor_test() {
+        let source = ParsedSource::parse(
+            "@Foo.named()\nclass A {}\n@p.Foo.named(1)\nclass B {}\n@p.Foo()\nclass C {}\n"
+                .to_owned(),
+        )
+        .unwrap();
+        let names = |name| {
+            classes_annotated(source.ast(), name)
+                .map(|class| class.node.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names("Foo"), vec!["A", "B", "C"]);
+        assert_eq!(names("p.Foo"), vec!["B", "C"]);
+        assert_eq!(names("named"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn query_members_test() {
+        let source = ParsedSource::parse(SOURCE.to_owned()).unwrap();
+        let user = classes(source.ast()).next().unwrap().node;
+
+        let fields = user.fields().collect::<Vec<_>>();
+        assert_eq!(
+            fields
+                .iter()
+                .map(|field| field.node.name)
+                .collect::<Vec<_>>(),
+            vec!["id", "tags"]
+        );
+        assert_eq!(
+            fields[0]
+                .annotation("JsonKey")
+                .and_then(|annotation| annotation.arg("name"))
+                .map(|arg| &arg.value),
+            Some(&Expr::String("id"))
+        );
+        assert!(matches!(
+            &fields[1].node.var_type,
+            Some(Type::NotFunc(ty)) if ty.name == "List" && ty.is_nullable
+        ));
+
+        assert_eq!(user.constructors().count(), 2);
+        assert_eq!(
+            user.constructor(Some("fromJson"))
+                .map(|constructor| constructor.node.name),
+            Some("User.fromJson")
+        );
+        assert_eq!(
+            user.constructor(None)
+                .map(|constructor| constructor.node.name),
+            Some("User")
+        );
+        assert_eq!(user.methods().count(), 1);
+    }
+
+    #[test]
+    fn query_enums_functions_test() {
+        let source = ParsedSource::parse(SOURCE.to_owned()).unwrap();
+
+        let enums = enums_implementing(source.ast(), "Comparable").collect::<Vec<_>>();
+        assert_eq!(enums.len(), 1);
+        assert_eq!(enums[0].node.name, "Role");
+        assert_eq!(enums[0].node.fields().count(), 1);
+
+        assert_eq!(
+            functions_returning(source.ast(), "Future")
+                .map(|func| func.node.name)
+                .collect::<Vec<_>>(),
+            vec!["fetchUser", "save"]
+        );
+        assert_eq!(functions(source.ast()).count(), 3);
+    }
+}
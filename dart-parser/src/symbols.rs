@@ -0,0 +1,477 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    dart::{
+        directive::{Export, Filter, Import},
+        Directive, FuncLike, NotFuncType,
+    },
+    library::normalize,
+    Dart, Library, PackageConfig, ParsedSource, UriTarget, WithMeta,
+};
+
+/// A set of libraries with their top-level scopes: the declarations of every
+/// library, the names it imports and the names it exports.
+#[derive(Debug)]
+pub struct Program {
+    libraries: Vec<Library>,
+    scopes: Vec<Scope>,
+    by_path: HashMap<PathBuf, usize>,
+    unresolved: Vec<String>,
+}
+
+/// A top-level declaration and the library it's declared in.
+#[derive(Clone, Copy, Debug)]
+pub struct Symbol<'a> {
+    pub library: &'a Library,
+    pub declaration: &'a WithMeta<'a, Dart<'a>>,
+}
+
+#[derive(Debug)]
+pub enum Resolution<'a> {
+    Declared(Symbol<'a>),
+    /// Imported from several libraries, which is a compile-time error
+    /// if the name is used.
+    Ambiguous(Vec<Symbol<'a>>),
+    /// Not declared in any of the loaded libraries, e.g. an SDK type
+    /// such as `String`, or a name from a library that can't be loaded.
+    Unresolved,
+}
+
+/// The position of a top-level item: the index of the unit in the library
+/// and the index of the item in the unit.
+type ItemId = (usize, usize);
+
+/// A top-level declaration: the index of the library and the item.
+type SymbolId = (usize, ItemId);
+
+#[derive(Debug, Default)]
+struct Scope {
+    declared: HashMap<String, Vec<ItemId>>,
+    /// The imports and exports with the index of the target library,
+    /// `None` for SDK libraries and for the unresolved ones.
+    imports: Vec<(ItemId, Option<usize>)>,
+    exports: Vec<(ItemId, Option<usize>)>,
+    /// The export namespace: the public declarations and the re-exports.
+    exported: HashMap<String, Vec<SymbolId>>,
+}
+
+impl Program {
+    /// Load the libraries at `paths` and every library they import or export,
+    /// except for the SDK libraries.
+    ///
+    /// The URIs are resolved with `config`, the files are loaded with `load`.
+    /// Only the libraries at `paths` must load, the imports and exports that
+    /// can't be resolved or loaded are recorded in [`Self::unresolved()`].
+    pub fn load<F>(
+        paths: impl IntoIterator<Item = PathBuf>,
+        config: &PackageConfig,
+        mut load: F,
+    ) -> Result<Self, String>
+    where
+        F: FnMut(&Path) -> Result<ParsedSource, String>,
+    {
+        let mut program = Self {
+            libraries: Vec::new(),
+            scopes: Vec::new(),
+            by_path: HashMap::new(),
+            unresolved: Vec::new(),
+        };
+
+        // The paths to load, `true` for the imported (or exported) ones
+        let mut queue = paths
+            .into_iter()
+            .map(|path| (normalize(&path), false))
+            .collect::<VecDeque<_>>();
+        let mut failed = HashSet::new();
+        while let Some((path, is_imported)) = queue.pop_front() {
+            if program.by_path.contains_key(&path) || failed.contains(&path) {
+                continue;
+            }
+
+            let library = match Library::load(path.clone(), config, &mut load) {
+                Ok(library) => library,
+                Err(err) if is_imported => {
+                    program.unresolved.push(err);
+                    failed.insert(path);
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            for (unit, uri) in library.units().iter().flat_map(|unit| {
+                unit.directives()
+                    .filter_map(|directive| match directive {
+                        Directive::Import(Import { target, .. })
+                        | Directive::Export(Export { target, .. }) => Some(*target),
+                        _ => None,
+                    })
+                    .map(move |uri| (unit, uri))
+            }) {
+                match config.resolve(uri, &unit.path) {
+                    Ok(UriTarget::File(path)) => queue.push_back((path, true)),
+                    Ok(UriTarget::Sdk(_)) => {}
+                    Err(err) => program.unresolved.push(format!(
+                        "Cannot resolve URI in file at path {:?}: {err}",
+                        unit.path
+                    )),
+                }
+            }
+
+            program.by_path.insert(path, program.libraries.len());
+            program.libraries.push(library);
+        }
+
+        program.build_scopes(config);
+
+        Ok(program)
+    }
+
+    pub fn libraries(&self) -> &[Library] {
+        &self.libraries
+    }
+
+    /// The errors of the imports and exports that couldn't be resolved
+    /// or loaded, the names they bring in are unresolved.
+    pub fn unresolved(&self) -> &[String] {
+        &self.unresolved
+    }
+
+    pub fn library(&self, path: &Path) -> Option<&Library> {
+        self.by_path
+            .get(&normalize(path))
+            .map(|&index| &self.libraries[index])
+    }
+
+    /// Resolve a name used at the top level of the library at `path`,
+    /// e.g. a type name, which can be prefixed (`json.JsonKey`).
+    ///
+    /// The declarations of the library itself shadow the imported ones.
+    /// Type parameters and other local names are not taken into account.
+    pub fn lookup(&self, path: &Path, name: &str) -> Result<Resolution<'_>, String> {
+        let index = *self
+            .by_path
+            .get(&normalize(path))
+            .ok_or_else(|| format!("No library at path {path:?}"))?;
+        let scope = &self.scopes[index];
+
+        let (prefix, name) = match name.split_once('.') {
+            Some((prefix, name)) => (Some(prefix), name),
+            None => (None, name),
+        };
+
+        if prefix.is_none() {
+            if let Some(&item) = scope.declared.get(name).and_then(|items| items.first()) {
+                return Ok(Resolution::Declared(self.symbol((index, item))));
+            }
+        }
+
+        let mut symbols = Vec::new();
+        for &(item, target) in &scope.imports {
+            let (import, Some(target)) = (self.import(index, item), target) else {
+                continue;
+            };
+            if import.prefix != prefix || !is_visible(&import.filters, name) {
+                continue;
+            }
+            for &symbol in self.scopes[target].exported.get(name).into_iter().flatten() {
+                if !symbols.contains(&symbol) {
+                    symbols.push(symbol);
+                }
+            }
+        }
+
+        Ok(match symbols.len() {
+            0 => Resolution::Unresolved,
+            1 => Resolution::Declared(self.symbol(symbols[0])),
+            _ => Resolution::Ambiguous(symbols.into_iter().map(|id| self.symbol(id)).collect()),
+        })
+    }
+
+    /// Resolve a type used at the top level of the library at `path`.
+    pub fn resolve_type(&self, path: &Path, ty: &NotFuncType) -> Result<Resolution<'_>, String> {
+        self.lookup(path, ty.name)
+    }
+
+    fn build_scopes(&mut self, config: &PackageConfig) {
+        for library in &self.libraries {
+            let mut scope = Scope::default();
+
+            for (unit_index, unit) in library.units().iter().enumerate() {
+                for (item_index, item) in unit.source.ast().iter().enumerate() {
+                    let item_id = (unit_index, item_index);
+                    let (target, is_import) = match item.as_ref() {
+                        Dart::Directive(Directive::Import(Import { target, .. })) => {
+                            (*target, true)
+                        }
+                        Dart::Directive(Directive::Export(Export { target, .. })) => {
+                            (*target, false)
+                        }
+                        item => {
+                            if let Some(name) = declared_name(item) {
+                                scope.declared.entry(name).or_default().push(item_id);
+                            }
+                            continue;
+                        }
+                    };
+
+                    let target = match config.resolve(target, &unit.path) {
+                        Ok(UriTarget::File(path)) => self.by_path.get(&path).copied(),
+                        Ok(UriTarget::Sdk(_)) | Err(_) => None,
+                    };
+                    if is_import {
+                        scope.imports.push((item_id, target));
+                    } else {
+                        scope.exports.push((item_id, target));
+                    }
+                }
+            }
+
+            self.scopes.push(scope);
+        }
+
+        for (index, scope) in self.scopes.iter_mut().enumerate() {
+            scope.exported = scope
+                .declared
+                .iter()
+                .filter(|(name, _)| !name.starts_with('_'))
+                .map(|(name, items)| {
+                    let symbols = items.iter().map(|&item| (index, item)).collect();
+                    (name.clone(), symbols)
+                })
+                .collect();
+        }
+
+        // The exports can be cyclic, so they are propagated until nothing changes
+        let mut is_changed = true;
+        while is_changed {
+            is_changed = false;
+
+            for index in 0..self.scopes.len() {
+                for &(item, target) in &self.scopes[index].exports.clone() {
+                    let Some(target) = target else {
+                        continue;
+                    };
+                    let Dart::Directive(Directive::Export(export)) = self.item(index, item) else {
+                        unreachable!("Not an export")
+                    };
+                    let reexported = self.scopes[target]
+                        .exported
+                        .iter()
+                        .filter(|(name, _)| is_visible(&export.filters, name))
+                        .map(|(name, symbols)| (name.clone(), symbols.clone()))
+                        .collect::<Vec<_>>();
+
+                    let exported = &mut self.scopes[index].exported;
+                    for (name, symbols) in reexported {
+                        let entry = exported.entry(name).or_default();
+                        for symbol in symbols {
+                            if !entry.contains(&symbol) {
+                                entry.push(symbol);
+                                is_changed = true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn item(&self, library: usize, (unit, item): ItemId) -> &Dart<'_> {
+        self.libraries[library].units()[unit].source.ast()[item].as_ref()
+    }
+
+    fn import(&self, library: usize, item: ItemId) -> &Import<'_> {
+        match self.item(library, item) {
+            Dart::Directive(Directive::Import(import)) => import,
+            _ => unreachable!("Not an import"),
+        }
+    }
+
+    fn symbol(&self, (library, (unit, item)): SymbolId) -> Symbol<'_> {
+        let library = &self.libraries[library];
+
+        Symbol {
+            library,
+            declaration: &library.units()[unit].source.ast()[item],
+        }
+    }
+}
+
+/// The name a top-level item declares, setters have a `=` appended
+/// (as in the Dart namespaces), so that they don't clash with the getters.
+fn declared_name(item: &Dart) -> Option<String> {
+    let name = match item {
+        Dart::TypeDef(typedef) => typedef.name,
+        Dart::Var(var) => var.name,
+        Dart::FuncLike(FuncLike::Func(func)) => func.name,
+        Dart::FuncLike(FuncLike::Getter(getter)) => getter.name,
+        Dart::FuncLike(FuncLike::Setter(setter)) => return Some(format!("{}=", setter.name)),
+        Dart::Class(class) => class.name,
+        Dart::Enum(enum_ty) => enum_ty.name,
+        Dart::Extension(extension) => extension.name?,
+        Dart::FuncLike(FuncLike::Operator(_)) | Dart::Directive(_) => return None,
+    };
+
+    Some(name.to_owned())
+}
+
+fn is_visible(filters: &[Filter], name: &str) -> bool {
+    // A setter is shown or hidden along with its getter
+    let name = name.strip_suffix('=').unwrap_or(name);
+
+    filters.iter().all(|filter| match filter {
+        Filter::Show(names) => names.contains(&name),
+        Filter::Hide(names) => !names.contains(&name),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::Package;
+
+    fn program(files: &[(&str, &str)]) -> Program {
+        let files = files
+            .iter()
+            .map(|(path, source)| (PathBuf::from(path), source.to_string()))
+            .collect::<HashMap<_, _>>();
+        let config = PackageConfig {
+            packages: ["app", "models", "other"]
+                .into_iter()
+                .map(|name| Package {
+                    name: name.to_owned(),
+                    root: PathBuf::from(name),
+                    lib: Path::new(name).join("lib"),
+                    language_version: None,
+                })
+                .collect(),
+        };
+
+        Program::load(
+            [PathBuf::from("app/lib/main.dart")],
+            &config,
+            |path| match files.get(path) {
+                Some(source) => ParsedSource::parse(source.clone()).map_err(|err| err.to_string()),
+                None => Err(format!("No file at path {path:?}")),
+            },
+        )
+        .unwrap()
+    }
+
+    fn declared_in(program: &Program, name: &str) -> Option<(PathBuf, String)> {
+        match program
+            .lookup(Path::new("app/lib/main.dart"), name)
+            .unwrap()
+        {
+            Resolution::Declared(symbol) => Some((
+                symbol.library.path().to_owned(),
+                declared_name(symbol.declaration.as_ref()).unwrap(),
+            )),
+            _ => None,
+        }
+    }
+
+    const FILES: [(&str, &str); 5] = [
+        (
+            "app/lib/main.dart",
+            "import 'dart:core';\n\
+             import 'package:models/models.dart' hide User;\n\
+             import 'package:other/other.dart' as other;\n\
+             import 'src/user.dart';\n\
+             class App {}\n",
+        ),
+        (
+            "app/lib/src/user.dart",
+            "class User {}\nclass _Private {}\n",
+        ),
+        (
+            "models/lib/models.dart",
+            "export 'src/base.dart' show Base, baseName;\n\
+             class User {}\n",
+        ),
+        (
+            "models/lib/src/base.dart",
+            "export '../models.dart';\nclass Base {}\nclass Hidden {}\n\
+             String get baseName => '';\nset baseName(String value) {}\n",
+        ),
+        (
+            "other/lib/other.dart",
+            "export 'package:models/models.dart';\n",
+        ),
+    ];
+
+    #[test]
+    fn lookup_test() {
+        let program = program(&FILES);
+
+        assert_eq!(program.libraries().len(), 5);
+        assert_eq!(
+            declared_in(&program, "App"),
+            Some((PathBuf::from("app/lib/main.dart"), "App".to_owned()))
+        );
+        assert_eq!(
+            declared_in(&program, "User"),
+            Some((PathBuf::from("app/lib/src/user.dart"), "User".to_owned()))
+        );
+        assert_eq!(
+            declared_in(&program, "Base"),
+            Some((PathBuf::from("models/lib/src/base.dart"), "Base".to_owned()))
+        );
+        assert_eq!(
+            declared_in(&program, "baseName="),
+            Some((
+                PathBuf::from("models/lib/src/base.dart"),
+                "baseName=".to_owned()
+            ))
+        );
+        assert_eq!(
+            declared_in(&program, "other.User"),
+            Some((PathBuf::from("models/lib/models.dart"), "User".to_owned()))
+        );
+        // Exported by `base.dart`, but not shown by `models.dart`
+        assert_eq!(declared_in(&program, "Hidden"), None);
+        assert_eq!(declared_in(&program, "other.Hidden"), None);
+        assert_eq!(declared_in(&program, "_Private"), None);
+        assert_eq!(declared_in(&program, "String"), None);
+    }
+
+    #[test]
+    fn lookup_ambiguous_test() {
+        let mut files = FILES;
+        files[0].1 = "import 'package:models/models.dart';\nimport 'src/user.dart';\n";
+        let program = program(&files);
+
+        let resolution = program
+            .resolve_type(Path::new("app/lib/main.dart"), &NotFuncType::name("User"))
+            .unwrap();
+
+        assert!(matches!(resolution, Resolution::Ambiguous(symbols) if symbols.len() == 2));
+        assert!(program
+            .lookup(Path::new("app/lib/none.dart"), "User")
+            .is_err());
+    }
+
+    #[test]
+    fn lookup_unresolved_test() {
+        let mut files = FILES;
+        files[0].1 = "import 'package:unknown/unknown.dart';
+                      import 'src/none.dart' as none;
+                      import 'src/user.dart';
+";
+        let program = program(&files);
+
+        assert_eq!(program.unresolved().len(), 2);
+        assert!(matches!(
+            program.lookup(Path::new("app/lib/main.dart"), "none.User"),
+            Ok(Resolution::Unresolved)
+        ));
+        assert_eq!(
+            declared_in(&program, "User"),
+            Some((PathBuf::from("app/lib/src/user.dart"), "User".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,392 @@
+use std::{fmt::Display, str};
+
+/// A byte-level tokenizer that validates UTF-8 lazily.
+///
+/// Outside of string literals and comments, Dart code is ASCII, so only
+/// the non-ASCII sequences are validated as they are encountered, and only
+/// the part of the input that is actually tokenized gets validated.
+/// It serves the lexing of fragments such as constant expressions,
+/// the parser itself works on `&str`.
+///
+/// A string literal is a single token, including its interpolations.
+/// Unterminated string literals and comments extend to the end of the input,
+/// leaving it to the parser to report them.
+#[derive(Clone, Debug)]
+pub struct Tokenizer<'s> {
+    s: &'s [u8],
+    offset: usize,
+    /// The nesting depth of string interpolations.
+    depth: usize,
+}
+
+/// Deeper string interpolations are scanned as a part of the string,
+/// to keep the recursion bounded.
+const MAX_INTERPOLATION_DEPTH: usize = 64;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Token<'s> {
+    pub kind: TokenKind,
+    pub text: &'s str,
+    pub offset: usize,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TokenKind {
+    Whitespace,
+    Comment,
+    String,
+    Identifier,
+    Number,
+    /// A single ASCII punctuation character.
+    Punct,
+    /// A non-ASCII character outside of string literals and comments.
+    Unknown,
+}
+
+/// An invalid UTF-8 sequence at the given offset.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Utf8Error {
+    pub offset: usize,
+}
+
+impl Display for Utf8Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid UTF-8 sequence at offset {}", self.offset)
+    }
+}
+
+impl std::error::Error for Utf8Error {}
+
+impl<'s> Tokenizer<'s> {
+    pub fn new(s: &'s [u8]) -> Self {
+        Self {
+            s,
+            offset: 0,
+            depth: 0,
+        }
+    }
+
+    /// The offset of the next token.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    fn next_token(&mut self) -> Result<Token<'s>, Utf8Error> {
+        let start = self.offset;
+        let kind = self.scan()?;
+
+        Ok(Token {
+            kind,
+            // SAFETY: the token is either ASCII or has been validated.
+            text: unsafe { str::from_utf8_unchecked(&self.s[start..self.offset]) },
+            offset: start,
+        })
+    }
+
+    fn scan(&mut self) -> Result<TokenKind, Utf8Error> {
+        let c = self.s[self.offset];
+        let kind = match c {
+            b' ' | b'\t' | b'\r' | b'\n' => {
+                self.skip_while(|c| matches!(c, b' ' | b'\t' | b'\r' | b'\n'));
+                TokenKind::Whitespace
+            }
+            b'/' if self.peek(1) == Some(b'/') => {
+                self.scan_comment_single_line()?;
+                TokenKind::Comment
+            }
+            b'/' if self.peek(1) == Some(b'*') => {
+                self.scan_comment_multi_line()?;
+                TokenKind::Comment
+            }
+            b'\'' | b'"' => {
+                self.scan_string(false)?;
+                TokenKind::String
+            }
+            b'r' if matches!(self.peek(1), Some(b'\'' | b'"')) => {
+                self.offset += 1;
+                self.scan_string(true)?;
+                TokenKind::String
+            }
+            b'0'..=b'9' => {
+                self.scan_number();
+                TokenKind::Number
+            }
+            c if is_ident_start(c) => {
+                self.skip_while(is_ident_char);
+                TokenKind::Identifier
+            }
+            c if c.is_ascii() => {
+                self.offset += 1;
+                TokenKind::Punct
+            }
+            _ => {
+                self.skip_char()?;
+                TokenKind::Unknown
+            }
+        };
+
+        Ok(kind)
+    }
+
+    fn scan_comment_single_line(&mut self) -> Result<(), Utf8Error> {
+        while let Some(c) = self.peek(0) {
+            match c {
+                b'\r' | b'\n' => break,
+                _ => self.skip_char()?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Multi-line comments can be nested.
+    fn scan_comment_multi_line(&mut self) -> Result<(), Utf8Error> {
+        let mut depth = 0;
+
+        while self.offset < self.s.len() {
+            if self.starts_with(b"/*") {
+                depth += 1;
+                self.offset += 2;
+            } else if self.starts_with(b"*/") {
+                depth -= 1;
+                self.offset += 2;
+                if depth == 0 {
+                    break;
+                }
+            } else {
+                self.skip_char()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Scan a string literal starting at the opening quote.
+    fn scan_string(&mut self, is_raw: bool) -> Result<(), Utf8Error> {
+        let quote = self.s[self.offset];
+        let is_triple = self.starts_with(&[quote; 3]);
+        let quote_len = if is_triple { 3 } else { 1 };
+        self.offset += quote_len;
+
+        while let Some(c) = self.peek(0) {
+            match c {
+                c if c == quote && (!is_triple || self.starts_with(&[quote; 3])) => {
+                    self.offset += quote_len;
+                    break;
+                }
+                b'\r' | b'\n' if !is_triple => break,
+                b'\\' if !is_raw => {
+                    self.offset += 1;
+                    if self.offset < self.s.len() {
+                        self.skip_char()?;
+                    }
+                }
+                b'$' if !is_raw
+                    && self.peek(1) == Some(b'{')
+                    && self.depth < MAX_INTERPOLATION_DEPTH =>
+                {
+                    self.offset += 2;
+                    self.depth += 1;
+                    let result = self.scan_interpolation();
+                    self.depth -= 1;
+                    result?;
+                }
+                _ => self.skip_char()?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Scan the code up to and including the closing bracket.
+    fn scan_interpolation(&mut self) -> Result<(), Utf8Error> {
+        let mut depth = 1;
+
+        while self.offset < self.s.len() {
+            match self.scan()? {
+                TokenKind::Punct if self.s[self.offset - 1] == b'{' => depth += 1,
+                TokenKind::Punct if self.s[self.offset - 1] == b'}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The numbers are scanned loosely (e.g. `0xFF`, `1.5e-3`).
+    fn scan_number(&mut self) {
+        while let Some(c) = self.peek(0) {
+            let is_number_char = c.is_ascii_alphanumeric()
+                || c == b'_'
+                || c == b'.' && self.peek(1).is_some_and(|c| c.is_ascii_digit())
+                || matches!(c, b'+' | b'-') && matches!(self.s[self.offset - 1], b'e' | b'E');
+            if !is_number_char {
+                break;
+            }
+            self.offset += 1;
+        }
+    }
+
+    /// Skip a single character, validating it if it's not ASCII.
+    fn skip_char(&mut self) -> Result<(), Utf8Error> {
+        let c = self.s[self.offset];
+        if c.is_ascii() {
+            self.offset += 1;
+            return Ok(());
+        }
+
+        let len = match c {
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => 0,
+        };
+        let is_valid = len > 0
+            && self
+                .s
+                .get(self.offset..self.offset + len)
+                .is_some_and(|seq| str::from_utf8(seq).is_ok());
+        if !is_valid {
+            return Err(Utf8Error {
+                offset: self.offset,
+            });
+        }
+        self.offset += len;
+
+        Ok(())
+    }
+
+    fn skip_while(&mut self, f: impl Fn(u8) -> bool) {
+        self.offset += self.s[self.offset..]
+            .iter()
+            .position(|&c| !f(c))
+            .unwrap_or(self.s.len() - self.offset);
+    }
+
+    fn starts_with(&self, prefix: &[u8]) -> bool {
+        self.s[self.offset..].starts_with(prefix)
+    }
+
+    fn peek(&self, n: usize) -> Option<u8> {
+        self.s.get(self.offset + n).copied()
+    }
+}
+
+impl<'s> Iterator for Tokenizer<'s> {
+    type Item = Result<Token<'s>, Utf8Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.s.len() {
+            return None;
+        }
+
+        let result = self.next_token();
+        if result.is_err() {
+            // Stop after the first error
+            self.offset = self.s.len();
+        }
+
+        Some(result)
+    }
+}
+
+impl<'s> std::iter::FusedIterator for Tokenizer<'s> {}
+
+fn is_ident_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_' || c == b'$'
+}
+
+fn is_ident_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_' || c == b'$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(s: &str) -> Vec<(TokenKind, &str)> {
+        Tokenizer::new(s.as_bytes())
+            .map(|token| token.map(|token| (token.kind, token.text)))
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn tokenizer_test() {
+        use TokenKind::*;
+
+        assert_eq!(
+            tokens("final x = 1.5e-3; // Ünïcödé\n"),
+            vec![
+                (Identifier, "final"),
+                (Whitespace, " "),
+                (Identifier, "x"),
+                (Whitespace, " "),
+                (Punct, "="),
+                (Whitespace, " "),
+                (Number, "1.5e-3"),
+                (Punct, ";"),
+                (Whitespace, " "),
+                (Comment, "// Ünïcödé"),
+                (Whitespace, "\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenizer_string_test() {
+        use TokenKind::*;
+
+        assert_eq!(
+            tokens(r#"f("a${g("}")}б", r'\', '''x'y''')"#),
+            vec![
+                (Identifier, "f"),
+                (Punct, "("),
+                (String, r#""a${g("}")}б""#),
+                (Punct, ","),
+                (Whitespace, " "),
+                (String, r"r'\'"),
+                (Punct, ","),
+                (Whitespace, " "),
+                (String, "'''x'y'''"),
+                (Punct, ")"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenizer_comment_nested_test() {
+        use TokenKind::*;
+
+        assert_eq!(
+            tokens("/* a /* b */ c */x"),
+            vec![(Comment, "/* a /* b */ c */"), (Identifier, "x")]
+        );
+    }
+
+    #[test]
+    fn tokenizer_interpolation_depth_test() {
+        let source = format!("'{}'", "${'".repeat(100_000));
+
+        assert!(Tokenizer::new(source.as_bytes()).all(|token| token.is_ok()));
+    }
+
+    #[test]
+    fn tokenizer_invalid_utf8_test() {
+        let source = b"var s = '\xE2\x82';";
+
+        assert_eq!(
+            Tokenizer::new(source).find_map(Result::err),
+            Some(Utf8Error { offset: 9 })
+        );
+        assert_eq!(
+            tokens("var s = '€';").last(),
+            Some(&(TokenKind::Punct, ";"))
+        );
+    }
+}
@@ -0,0 +1,487 @@
+//! The type algebra needed by the code generators: nullability,
+//! the value types of `Future`, `Stream` and the collections,
+//! substitution and printing.
+//!
+//! The types are matched by name, an import prefix is ignored
+//! (`async.Future` is a `Future`).
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::dart::{
+    func_like::{FuncParams, FuncParamsExtra},
+    ty::{FuncType, FuncTypeParamNamed, FuncTypeParamPos, Tuple, Type},
+    NotFuncType, TypeParam, WithMeta,
+};
+
+impl<'s> Type<'s> {
+    /// Whether `null` is a value of the type: a type marked with `?`,
+    /// `dynamic`, `void`, `Null`, or `FutureOr<T>` of such a `T`.
+    pub fn accepts_null(&self) -> bool {
+        match self {
+            Type::NotFunc(ty) => ty.accepts_null(),
+            Type::Func(func_type) => func_type.is_nullable,
+            Type::Tuple(tuple) => tuple.is_nullable,
+        }
+    }
+
+    /// The type with or without the `?` mark.
+    pub fn with_nullable(self, is_nullable: bool) -> Type<'s> {
+        match self {
+            Type::NotFunc(ty) => Type::NotFunc(NotFuncType { is_nullable, ..ty }),
+            Type::Func(mut func_type) => {
+                func_type.is_nullable = is_nullable;
+                Type::Func(func_type)
+            }
+            Type::Tuple(tuple) => Type::Tuple(Tuple {
+                is_nullable,
+                ..tuple
+            }),
+        }
+    }
+
+    /// The type without the `?` mark, e.g. `int` for `int?`.
+    pub fn into_non_nullable(self) -> Type<'s> {
+        self.with_nullable(false)
+    }
+
+    /// Replace the type parameters with the type arguments, e.g. `List<T>`
+    /// becomes `List<int>` with `[("T", int)]`.
+    ///
+    /// The type parameters of a generic function type shadow the outer ones.
+    pub fn substitute(&self, args: &[(&str, Type<'s>)]) -> Type<'s> {
+        match self {
+            Type::NotFunc(ty) => {
+                let arg = args
+                    .iter()
+                    .find(|(name, _)| *name == ty.name && ty.type_args.is_empty());
+                match arg {
+                    Some((_, arg)) if ty.is_nullable => arg.clone().with_nullable(true),
+                    Some((_, arg)) => arg.clone(),
+                    None => Type::NotFunc(ty.substitute(args)),
+                }
+            }
+            Type::Func(func_type) => {
+                let args = args
+                    .iter()
+                    .filter(|(name, _)| {
+                        !func_type
+                            .type_params
+                            .iter()
+                            .any(|type_param| type_param.as_ref().name == *name)
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                Type::func(FuncType {
+                    return_type: func_type.return_type.substitute(&args),
+                    type_params: func_type
+                        .type_params
+                        .iter()
+                        .map(|type_param| {
+                            type_param.clone().map(|type_param| TypeParam {
+                                extends: type_param.extends.map(|ty| ty.substitute(&args)),
+                                ..type_param
+                            })
+                        })
+                        .collect(),
+                    params: substitute_params(&func_type.params, &args),
+                    is_nullable: func_type.is_nullable,
+                })
+            }
+            Type::Tuple(tuple) => Type::Tuple(Tuple {
+                params_pos: tuple
+                    .params_pos
+                    .iter()
+                    .map(|param| param.substitute(args))
+                    .collect(),
+                params_named: tuple
+                    .params_named
+                    .iter()
+                    .map(|param| param.substitute(args))
+                    .collect(),
+                is_nullable: tuple.is_nullable,
+            }),
+        }
+    }
+
+    /// The value type of a `Future` or a `FutureOr`, e.g. `int` for
+    /// `Future<int>?`.
+    pub fn future_type(&self) -> Option<Type<'s>> {
+        self.type_arg(&["Future", "FutureOr"])
+    }
+
+    /// The value type of a `Stream`.
+    pub fn stream_type(&self) -> Option<Type<'s>> {
+        self.type_arg(&["Stream"])
+    }
+
+    /// The type of the elements of a `List`, a `Set` or an `Iterable`.
+    pub fn element_type(&self) -> Option<Type<'s>> {
+        self.type_arg(&["List", "Set", "Iterable"])
+    }
+
+    /// The key and value types of a `Map`.
+    pub fn map_types(&self) -> Option<(Type<'s>, Type<'s>)> {
+        match self {
+            Type::NotFunc(ty) if ty.base_name() == "Map" => {
+                Some((type_arg_or_dynamic(ty, 0), type_arg_or_dynamic(ty, 1)))
+            }
+            _ => None,
+        }
+    }
+
+    /// The first type argument of a generic class with one of the names,
+    /// `dynamic` if omitted.
+    fn type_arg(&self, names: &[&str]) -> Option<Type<'s>> {
+        match self {
+            Type::NotFunc(ty) if names.contains(&ty.base_name()) => {
+                Some(type_arg_or_dynamic(ty, 0))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'s> NotFuncType<'s> {
+    /// The name without the import prefix, e.g. `Future` for `async.Future`.
+    pub fn base_name(&self) -> &'s str {
+        self.name
+            .rsplit_once('.')
+            .map_or(self.name, |(_, name)| name)
+    }
+
+    /// See [`Type::accepts_null`].
+    pub fn accepts_null(&self) -> bool {
+        self.is_nullable
+            || match self.base_name() {
+                "dynamic" | "void" | "Null" => true,
+                "FutureOr" => self.type_args.first().is_none_or(Type::accepts_null),
+                _ => false,
+            }
+    }
+
+    /// Substitute the type arguments, see [`Type::substitute`].
+    pub fn substitute(&self, args: &[(&str, Type<'s>)]) -> NotFuncType<'s> {
+        NotFuncType {
+            name: self.name,
+            type_args: self
+                .type_args
+                .iter()
+                .map(|ty| ty.substitute(args))
+                .collect(),
+            is_nullable: self.is_nullable,
+        }
+    }
+}
+
+impl<'s> FuncTypeParamPos<'s> {
+    fn substitute(&self, args: &[(&str, Type<'s>)]) -> Self {
+        Self {
+            param_type: self.param_type.substitute(args),
+            name: self.name,
+        }
+    }
+}
+
+impl<'s> FuncTypeParamNamed<'s> {
+    fn substitute(&self, args: &[(&str, Type<'s>)]) -> Self {
+        Self {
+            param_type: self.param_type.substitute(args),
+            name: self.name,
+        }
+    }
+}
+
+fn substitute_params<'s>(
+    params: &FuncParams<'s, FuncTypeParamPos<'s>, FuncTypeParamNamed<'s>>,
+    args: &[(&str, Type<'s>)],
+) -> FuncParams<'s, FuncTypeParamPos<'s>, FuncTypeParamNamed<'s>> {
+    FuncParams {
+        positional_req: params
+            .positional_req
+            .iter()
+            .map(|param| param.clone().map(|param| param.substitute(args)))
+            .collect(),
+        extra: params.extra.as_ref().map(|extra| match extra {
+            FuncParamsExtra::PositionalOpt(params) => FuncParamsExtra::PositionalOpt(
+                params
+                    .iter()
+                    .map(|param| param.clone().map(|param| param.substitute(args)))
+                    .collect(),
+            ),
+            FuncParamsExtra::Named(params) => FuncParamsExtra::Named(
+                params
+                    .iter()
+                    .map(|param| {
+                        param
+                            .clone()
+                            .map(|param| param.map(|param| param.substitute(args)))
+                    })
+                    .collect(),
+            ),
+        }),
+    }
+}
+
+/// The type arguments for the type parameters, as used by
+/// [`Type::substitute`]: the missing ones default to the bounds
+/// (or `dynamic`), the extra ones are ignored.
+pub fn type_args<'s>(
+    type_params: &[WithMeta<'s, TypeParam<'s>>],
+    type_args: &[Type<'s>],
+) -> Vec<(&'s str, Type<'s>)> {
+    type_params
+        .iter()
+        .enumerate()
+        .map(|(i, type_param)| {
+            let type_param = type_param.as_ref();
+            let arg = type_args.get(i).cloned().unwrap_or_else(|| {
+                type_param
+                    .extends
+                    .clone()
+                    .unwrap_or(Type::NotFunc(NotFuncType::dynamic()))
+            });
+            (type_param.name, arg)
+        })
+        .collect()
+}
+
+fn type_arg_or_dynamic<'s>(ty: &NotFuncType<'s>, i: usize) -> Type<'s> {
+    ty.type_args
+        .get(i)
+        .cloned()
+        .unwrap_or(Type::NotFunc(NotFuncType::dynamic()))
+}
+
+/// The canonical form of a type, without the comments and annotations,
+/// e.g. `Map<String, int?>` or `void Function(int, {required String name})`.
+impl Display for Type<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::NotFunc(ty) => ty.fmt(f),
+            Type::Func(func_type) => func_type.fmt(f),
+            Type::Tuple(tuple) => tuple.fmt(f),
+        }
+    }
+}
+
+impl Display for NotFuncType<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)?;
+        if !self.type_args.is_empty() {
+            f.write_str("<")?;
+            write_list(f, &self.type_args)?;
+            f.write_str(">")?;
+        }
+        write_nullable(f, self.is_nullable)
+    }
+}
+
+impl Display for FuncType<'_> {
+    /// The names of the positional parameters are omitted.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} Function", self.return_type)?;
+        if !self.type_params.is_empty() {
+            f.write_str("<")?;
+            for (i, type_param) in self.type_params.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                let type_param = type_param.as_ref();
+                f.write_str(type_param.name)?;
+                if let Some(extends) = &type_param.extends {
+                    write!(f, " extends {extends}")?;
+                }
+            }
+            f.write_str(">")?;
+        }
+        f.write_str("(")?;
+        write_params(f, &self.params)?;
+        f.write_str(")")?;
+        write_nullable(f, self.is_nullable)
+    }
+}
+
+impl Display for Tuple<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("(")?;
+        write_list(f, self.params_pos.iter().map(|param| &param.param_type))?;
+        if self.params_pos.len() == 1 && self.params_named.is_empty() {
+            f.write_str(",")?;
+        } else if !self.params_pos.is_empty() && !self.params_named.is_empty() {
+            f.write_str(", ")?;
+        }
+        if !self.params_named.is_empty() {
+            f.write_str("{")?;
+            write_named(f, self.params_named.iter().map(|param| (false, param)))?;
+            f.write_str("}")?;
+        }
+        f.write_str(")")?;
+        write_nullable(f, self.is_nullable)
+    }
+}
+
+fn write_params(
+    f: &mut Formatter<'_>,
+    params: &FuncParams<'_, FuncTypeParamPos<'_>, FuncTypeParamNamed<'_>>,
+) -> fmt::Result {
+    let positional_req = params
+        .positional_req
+        .iter()
+        .map(|param| &param.as_ref().param_type);
+    write_list(f, positional_req)?;
+
+    let Some(extra) = &params.extra else {
+        return Ok(());
+    };
+    if !params.positional_req.is_empty() {
+        f.write_str(", ")?;
+    }
+    match extra {
+        FuncParamsExtra::PositionalOpt(params) => {
+            f.write_str("[")?;
+            write_list(f, params.iter().map(|param| &param.as_ref().param_type))?;
+            f.write_str("]")
+        }
+        FuncParamsExtra::Named(params) => {
+            f.write_str("{")?;
+            write_named(
+                f,
+                params.iter().map(|param| {
+                    let param = param.as_ref();
+                    (param.is_required, param.as_ref())
+                }),
+            )?;
+            f.write_str("}")
+        }
+    }
+}
+
+fn write_named<'a, 's: 'a>(
+    f: &mut Formatter<'_>,
+    params: impl IntoIterator<Item = (bool, &'a FuncTypeParamNamed<'s>)>,
+) -> fmt::Result {
+    for (i, (is_required, param)) in params.into_iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        if is_required {
+            f.write_str("required ")?;
+        }
+        write!(f, "{} {}", param.param_type, param.name)?;
+    }
+    Ok(())
+}
+
+fn write_list<'a, 's: 'a>(
+    f: &mut Formatter<'_>,
+    types: impl IntoIterator<Item = &'a Type<'s>>,
+) -> fmt::Result {
+    for (i, ty) in types.into_iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        ty.fmt(f)?;
+    }
+    Ok(())
+}
+
+fn write_nullable(f: &mut Formatter<'_>, is_nullable: bool) -> fmt::Result {
+    if is_nullable {
+        f.write_str("?")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_type;
+
+    #[test]
+    fn type_nullable_test() {
+        let accepts_null = |s| parse_type(s).unwrap().accepts_null();
+
+        assert!(accepts_null("int?"));
+        assert!(!accepts_null("int"));
+        assert!(accepts_null("dynamic"));
+        assert!(accepts_null("FutureOr<String?>"));
+        assert!(!accepts_null("FutureOr<String>"));
+        assert!(accepts_null("void Function()?"));
+        assert!(accepts_null("(int, String)?"));
+
+        let ty = parse_type("List<int?>?").unwrap();
+        assert_eq!(ty.clone().into_non_nullable().to_string(), "List<int?>");
+        assert_eq!(
+            ty.into_non_nullable().with_nullable(true).to_string(),
+            "List<int?>?"
+        );
+    }
+
+    #[test]
+    fn type_unwrap_test() {
+        let ty = |s| parse_type(s).unwrap();
+        let display = |ty: Option<Type>| ty.map(|ty| ty.to_string());
+
+        assert_eq!(
+            display(ty("Future<List<int>>").future_type()),
+            Some("List<int>".to_owned())
+        );
+        assert_eq!(
+            display(ty("async.FutureOr<int?>").future_type()),
+            Some("int?".to_owned())
+        );
+        assert_eq!(
+            display(ty("Future").future_type()),
+            Some("dynamic".to_owned())
+        );
+        assert_eq!(
+            display(ty("Stream<User>?").stream_type()),
+            Some("User".to_owned())
+        );
+        assert_eq!(display(ty("Stream<User>").future_type()), None);
+        assert_eq!(
+            display(ty("Set<String>").element_type()),
+            Some("String".to_owned())
+        );
+        assert_eq!(display(ty("Map<String, int>").element_type()), None);
+        assert_eq!(
+            ty("Map<String, List<int>>")
+                .map_types()
+                .map(|(key, value)| (key.to_string(), value.to_string())),
+            Some(("String".to_owned(), "List<int>".to_owned()))
+        );
+    }
+
+    #[test]
+    fn type_substitute_display_test() {
+        let class_type_params = [
+            WithMeta::value(TypeParam {
+                name: "K",
+                extends: None,
+            }),
+            WithMeta::value(TypeParam {
+                name: "V",
+                extends: Some(parse_type("Object").unwrap()),
+            }),
+        ];
+        let args = type_args(&class_type_params, &[parse_type("String").unwrap()]);
+        let ty = parse_type(
+            "Map<K, V?> Function<T extends K>(K key, T, [V? value]) Function({required V v, K? k})",
+        )
+        .unwrap();
+
+        assert_eq!(
+            ty.substitute(&args).to_string(),
+            "Map<String, Object?> Function<T extends String>(String, T, [Object?]) \
+            Function({required Object v, String? k})"
+        );
+        assert_eq!(
+            parse_type("(int, {String? name})?").unwrap().to_string(),
+            "(int, {String? name})?"
+        );
+        assert_eq!(parse_type("(int,)").unwrap().to_string(), "(int,)");
+        assert_eq!(parse_type("()").unwrap().to_string(), "()");
+        assert_eq!(parse_type("({int a})").unwrap().to_string(), "({int a})");
+    }
+}
@@ -0,0 +1,399 @@
+use std::{fmt::Display, ops::Range};
+
+use crate::{
+    dart::{
+        class::{ClassMember, ClassModifier, Constructor, ConstructorModifier},
+        extension::ExtensionMember,
+        func_like::{
+            FuncBody, FuncBodyContent, FuncBodyModifier, FuncLike, FuncModifier, FuncParam,
+            FuncParamModifier, FuncParams, FuncParamsExtra, Setter,
+        },
+        var::VarModifier,
+        Class, Var,
+    },
+    Dart, LineIndex, WithMeta,
+};
+
+/// A declaration the parser accepts but Dart forbids.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Diagnostic {
+    /// The byte range of the name of the declaration.
+    pub range: Range<usize>,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Check the rules the AST doesn't enforce: the combinations of class
+/// modifiers, the setter parameters, the use of `covariant` and the
+/// function bodies.
+///
+/// # Panics
+///
+/// If `ast` doesn't borrow from `source`.
+pub fn validate<'s>(source: &'s str, ast: &[WithMeta<'s, Dart<'s>>]) -> Vec<Diagnostic> {
+    let mut validator = Validator {
+        line_index: LineIndex::new(source),
+        diagnostics: Vec::new(),
+    };
+
+    for item in ast {
+        match item.as_ref() {
+            Dart::Var(var) => validator.top_level_var(var),
+            Dart::FuncLike(func_like) => validator.func_like(func_like, Context::TopLevel),
+            Dart::Class(class) => validator.class(class),
+            Dart::Enum(enum_ty) => validator.members(&enum_ty.members),
+            Dart::Extension(extension) => {
+                for member in &extension.body {
+                    match member.as_ref() {
+                        ExtensionMember::Var(var) => validator.top_level_var(var),
+                        ExtensionMember::FuncLike(func_like) => {
+                            validator.func_like(func_like, Context::Extension)
+                        }
+                    }
+                }
+            }
+            Dart::Directive(_) | Dart::TypeDef(_) => {}
+        }
+    }
+
+    validator.diagnostics
+}
+
+/// Where a function is declared, `covariant` parameters are only allowed
+/// in instance members of classes, mixins and enums.
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum Context {
+    TopLevel,
+    Class,
+    Extension,
+}
+
+struct Validator<'s> {
+    line_index: LineIndex<'s>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'s> Validator<'s> {
+    /// `name` is the slice of the source the diagnostic points at.
+    fn error(&mut self, name: &'s str, message: String) {
+        self.diagnostics.push(Diagnostic {
+            range: self
+                .line_index
+                .range(name)
+                .expect("the AST borrows from the source"),
+            message,
+        });
+    }
+
+    fn class(&mut self, class: &Class<'s>) {
+        if let Some(message) = class_modifiers_error(class) {
+            self.error(class.name, message);
+        }
+
+        self.members(&class.body);
+    }
+
+    fn members(&mut self, members: &[WithMeta<'s, ClassMember<'s>>]) {
+        for member in members {
+            match member.as_ref() {
+                ClassMember::Constructor(constructor) => self.constructor(constructor),
+                ClassMember::Var(var) => {
+                    if var.modifiers.contains(VarModifier::Covariant) {
+                        if var.modifiers.contains(VarModifier::Static) {
+                            self.error(
+                                var.name,
+                                format!("Static field `{}` cannot be covariant", var.name),
+                            );
+                        } else if !var.has_setter() {
+                            self.error(
+                                var.name,
+                                format!("Final field `{}` cannot be covariant", var.name),
+                            );
+                        }
+                    }
+                }
+                ClassMember::FuncLike(func_like) => self.func_like(func_like, Context::Class),
+            }
+        }
+    }
+
+    /// A top-level variable or a field of an extension.
+    fn top_level_var(&mut self, var: &Var<'s>) {
+        if var.modifiers.contains(VarModifier::Covariant) {
+            self.error(
+                var.name,
+                format!("Only instance fields can be covariant, not `{}`", var.name),
+            );
+        }
+    }
+
+    fn constructor(&mut self, constructor: &Constructor<'s>) {
+        let is_factory = constructor.modifier == Some(ConstructorModifier::Factory);
+        let is_const = constructor.modifier == Some(ConstructorModifier::Const);
+
+        match &constructor.body {
+            Some(FuncBodyContent::Expr(_)) if !is_factory => self.error(
+                constructor.name,
+                format!(
+                    "Generative constructor `{}` cannot have an expression body",
+                    constructor.name
+                ),
+            ),
+            Some(FuncBodyContent::Block(_)) if is_const => self.error(
+                constructor.name,
+                format!(
+                    "Const constructor `{}` cannot have a body",
+                    constructor.name
+                ),
+            ),
+            _ => {}
+        }
+
+        self.covariant_params(constructor.name, &constructor.params, false);
+    }
+
+    fn func_like(&mut self, func_like: &FuncLike<'s>, context: Context) {
+        let (name, modifiers, params, body) = match func_like {
+            FuncLike::Func(func) => (func.name, func.modifiers, Some(&func.params), &func.body),
+            FuncLike::Operator(operator) => (
+                operator.name,
+                operator.modifiers,
+                Some(&operator.params),
+                &operator.body,
+            ),
+            FuncLike::Getter(getter) => (getter.name, getter.modifiers, None, &getter.body),
+            FuncLike::Setter(setter) => {
+                self.setter_params(setter);
+                (
+                    setter.name,
+                    setter.modifiers,
+                    Some(&setter.params),
+                    &setter.body,
+                )
+            }
+        };
+
+        let label = match func_like {
+            FuncLike::Operator(_) => format!("operator {name}"),
+            _ => name.to_owned(),
+        };
+
+        if let Some(params) = params {
+            let allows_covariant =
+                context == Context::Class && !modifiers.contains(FuncModifier::Static);
+            self.covariant_params(&label, params, allows_covariant);
+        }
+
+        if let Some(FuncBody {
+            modifier: Some(FuncBodyModifier::SyncGenerator | FuncBodyModifier::AsyncGenerator),
+            content: FuncBodyContent::Expr(_),
+        }) = body
+        {
+            self.error(
+                name,
+                format!("Generator `{label}` cannot have an expression body"),
+            );
+        }
+    }
+
+    fn setter_params(&mut self, setter: &Setter<'s>) {
+        if setter.params.positional_req.len() != 1 || setter.params.extra.is_some() {
+            self.error(
+                setter.name,
+                format!(
+                    "Setter `{}` must have exactly one required positional parameter",
+                    setter.name
+                ),
+            );
+        }
+    }
+
+    fn covariant_params(
+        &mut self,
+        name: &str,
+        params: &FuncParams<'s, FuncParam<'s>>,
+        allows_covariant: bool,
+    ) {
+        if allows_covariant {
+            return;
+        }
+
+        let positional = params
+            .positional_req
+            .iter()
+            .chain(match &params.extra {
+                Some(FuncParamsExtra::PositionalOpt(params)) => params.as_slice(),
+                _ => &[],
+            })
+            .map(|param| param.as_ref());
+        let named = match &params.extra {
+            Some(FuncParamsExtra::Named(params)) => params.as_slice(),
+            _ => &[],
+        }
+        .iter()
+        .map(|param| param.as_ref().as_ref());
+
+        for param in positional.chain(named) {
+            if param.modifiers.contains(FuncParamModifier::Covariant) {
+                self.error(
+                    param.name,
+                    format!(
+                        "Parameter `{}` of `{name}` cannot be covariant, only the parameters \
+                        of instance methods can",
+                        param.name
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// See [`ClassModifier`] for the legal combinations.
+fn class_modifiers_error(class: &Class) -> Option<String> {
+    let modifiers = class.modifiers;
+    let is_class = modifiers.contains(ClassModifier::Class);
+    let is_mixin = modifiers.contains(ClassModifier::Mixin);
+    let restrictions = [
+        ClassModifier::Base,
+        ClassModifier::Final,
+        ClassModifier::Interface,
+        ClassModifier::Sealed,
+    ]
+    .into_iter()
+    .filter(|modifier| modifiers.contains(*modifier))
+    .collect::<Vec<_>>();
+    let name = class.name;
+
+    if !is_class && !is_mixin {
+        return Some(format!("`{name}` must be declared as a class or a mixin"));
+    }
+    if is_mixin
+        && (restrictions
+            .iter()
+            .any(|modifier| *modifier != ClassModifier::Base))
+    {
+        return Some(format!(
+            "Only the `base` modifier can be used on mixin `{name}`"
+        ));
+    }
+    if is_mixin && !is_class && modifiers.contains(ClassModifier::Abstract) {
+        return Some(format!("Mixin `{name}` cannot be abstract"));
+    }
+    if is_mixin && is_class && !class.mixin_on.is_empty() {
+        return Some(format!("Mixin class `{name}` cannot have an `on` clause"));
+    }
+    if restrictions.len() > 1 {
+        return Some(format!(
+            "Class `{name}` can have only one of the `base`, `final`, `interface` \
+            and `sealed` modifiers"
+        ));
+    }
+    if modifiers.contains(ClassModifier::Sealed) && modifiers.contains(ClassModifier::Abstract) {
+        return Some(format!(
+            "Sealed class `{name}` is implicitly abstract, `abstract` is not allowed"
+        ));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParsedSource;
+
+    fn messages(source: &str) -> Vec<(String, String)> {
+        let source = ParsedSource::parse(source.to_owned()).unwrap();
+
+        validate(source.source(), source.ast())
+            .into_iter()
+            .map(|diagnostic| {
+                (
+                    source.source()[diagnostic.range].to_owned(),
+                    diagnostic.message,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn validate_class_modifiers_test() {
+        let errors = messages(
+            "abstract base mixin class A {}\n\
+            base mixin B {}\n\
+            final mixin C {}\n\
+            sealed final class D {}\n\
+            abstract sealed class E {}\n\
+            abstract final class F {}\n",
+        );
+
+        assert_eq!(
+            errors
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["C", "D", "E"]
+        );
+    }
+
+    #[test]
+    fn validate_members_test() {
+        let errors = messages(
+            "covariant int a = 0;\n\
+            set b(int x, int y) {}\n\
+            void c(covariant int x) {}\n\
+            class D {\n\
+            \x20 covariant num e = 0;\n\
+            \x20 covariant final num f = 0;\n\
+            \x20 D(this.e) => print(e);\n\
+            \x20 D.named();\n\
+            \x20 factory D.create() => D.named();\n\
+            \x20 const D.constant() {}\n\
+            \x20 void g(covariant num x) {}\n\
+            \x20 static void h(covariant num x) {}\n\
+            \x20 set i([int x = 0]) {}\n\
+            \x20 Iterable<int> j() sync* => [];\n\
+            \x20 covariant late final num k;\n\
+            \x20 covariant late final num l = 0;\n\
+            }\n\
+            bool operator ==(covariant Object other) => false;\n\
+            class M {\n\
+            \x20 Iterable<int> operator -() sync* => [];\n\
+            }\n",
+        );
+
+        assert_eq!(
+            errors
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "a",
+                "b",
+                "x",
+                "f",
+                "D",
+                "D.constant",
+                "x",
+                "i",
+                "j",
+                "l",
+                "other",
+                "-"
+            ]
+        );
+        assert_eq!(
+            errors[4].1,
+            "Generative constructor `D` cannot have an expression body"
+        );
+        assert_eq!(
+            errors[11].1,
+            "Generator `operator -` cannot have an expression body"
+        );
+    }
+}
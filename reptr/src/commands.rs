@@ -2,4 +2,3 @@ pub mod parse_file;
 pub mod scan;
 
 pub use parse_file::parse_file;
-pub use scan::scan_dirs;
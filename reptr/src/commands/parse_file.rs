@@ -1,9 +1,12 @@
 use std::{fs, io, path};
 
-use crate::common::ErrorContext;
+use crate::common::{decode_source, DecodedSource, ErrorContext};
 
-pub fn parse_file(path: path::PathBuf) -> io::Result<()> {
-    let source = read_string(&path)?;
+pub fn parse_file(path: path::PathBuf, latin1: bool) -> io::Result<()> {
+    let DecodedSource { source, warning } = read_string(&path, latin1)?;
+    if let Some(warning) = warning {
+        eprintln!("Warning: file at path {path:?}: {warning}");
+    }
     match dart_parser::parse(&source) {
         Ok(ast) => {
             println!("{ast:#?}");
@@ -15,8 +18,8 @@ pub fn parse_file(path: path::PathBuf) -> io::Result<()> {
     Ok(())
 }
 
-fn read_string(path: &path::Path) -> io::Result<String> {
-    String::from_utf8(read(path)?).map_err(|e| {
+fn read_string(path: &path::Path, latin1: bool) -> io::Result<DecodedSource> {
+    decode_source(read(path)?, latin1).map_err(|e| {
         io::Error::new(
             io::ErrorKind::InvalidData,
             format!("Cannot load file at path {path:?}: {e}"),
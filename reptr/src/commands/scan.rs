@@ -1,3 +1,4 @@
+mod deps;
 mod find_dart_files;
 mod parse_raw;
 mod stats;
@@ -7,7 +8,12 @@ use std::{env, io, path, sync::Arc};
 
 use crate::common::poisoned;
 
-use self::{find_dart_files::find_dart_files, parse_raw::parse_raw_async, stats::Counter};
+use self::{
+    deps::find_deps,
+    find_dart_files::{find_dart_files, find_dep_dart_files, FileContext},
+    parse_raw::parse_raw_async,
+    stats::Counter,
+};
 
 macro_rules! time {
     ($x:expr) => {{
@@ -27,10 +33,15 @@ pub(crate) use time;
 pub struct Options {
     pub ignore: Vec<path::PathBuf>,
     pub quiet: bool,
+    /// Decode the files that are not valid UTF-8 as Latin-1.
+    pub latin1: bool,
+    /// Parse the dependencies of the packages as well, read-only.
+    pub deps: bool,
 }
 
 pub async fn scan_dirs(dir_paths: Vec<path::PathBuf>, options: Options) -> io::Result<()> {
     let stats = Arc::new(std::sync::Mutex::new(stats::Stats::default()));
+    let latin1 = options.latin1;
 
     let println = move |s: String| {
         if !options.quiet {
@@ -48,52 +59,75 @@ pub async fn scan_dirs(dir_paths: Vec<path::PathBuf>, options: Options) -> io::R
     let mut tasks = tokio::task::JoinSet::new();
     let async_read_throttle = Arc::new(tokio::sync::Semaphore::new(16));
 
+    let mut spawn = |entry: io::Result<(FileContext, path::PathBuf)>| match entry {
+        Ok((context, path)) => {
+            let async_read_throttle = async_read_throttle.clone();
+            let stats = stats.clone();
+            let read_only = context.read_only;
+
+            let rel_path = path.strip_prefix(&cwd).unwrap_or(&path).to_owned();
+            let rel_path_copy = rel_path.clone();
+
+            tasks.spawn(
+                async move {
+                    let source = read_async(&path, async_read_throttle, stats.clone()).await?;
+
+                    parse_raw_async(source, latin1, read_only, stats)
+                        .await
+                        .map_err(|e| {
+                            io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!("Error parsing file at path {rel_path:?}\n\n{e}"),
+                            )
+                        })
+                }
+                .map(|result| (context, rel_path_copy, result)),
+            );
+        }
+        Err(e) => {
+            eprintln!("Error scanning filesystem: {e}");
+        }
+    };
+
+    let mut pkg_dirs = Vec::new();
     for dir in dir_paths {
         for entry in find_dart_files(&dir, options.ignore.clone()) {
-            match entry {
-                Ok((context, path)) => {
-                    let async_read_throttle = async_read_throttle.clone();
-                    let stats = stats.clone();
-                    let cwd = cwd.clone();
-
-                    let rel_path = path.strip_prefix(&cwd).unwrap_or(&path).to_owned();
-                    let rel_path_copy = rel_path.clone();
-
-                    tasks.spawn(
-                        async move {
-                            let source =
-                                read_async(&path, async_read_throttle, stats.clone()).await?;
-
-                            parse_raw_async(source, stats).await.map_err(|e| {
-                                io::Error::new(
-                                    io::ErrorKind::InvalidData,
-                                    format!("Error parsing file at path {rel_path:?}\n\n{e}"),
-                                )
-                            })
-                        }
-                        .map(|result| (context, rel_path_copy, result)),
-                    );
-                }
-                Err(e) => {
-                    eprintln!("Error scanning filesystem: {e}");
+            if let Ok((context, _)) = &entry {
+                if !pkg_dirs.contains(&context.pkg_dir) {
+                    pkg_dirs.push(context.pkg_dir.clone());
                 }
             }
+            spawn(entry);
+        }
+    }
+
+    if options.deps {
+        for dep in find_deps(&pkg_dirs) {
+            match dep {
+                Ok(context) => find_dep_dart_files(context).for_each(&mut spawn),
+                Err(e) => println(format!("[WARNING] {e}")),
+            }
         }
     }
 
     while let Some(result) = tasks.join_next().await {
         match result {
             Ok(result) => {
-                let (context, _rel_path, result) = result;
+                let (context, rel_path, result) = result;
                 match result {
-                    Ok(_ast) => {
+                    Ok((_ast, Some(warning))) => {
+                        println(format!(
+                            "[WARNING] [{}] File at path {rel_path:?}: {warning}",
+                            context.label()
+                        ));
+                    }
+                    Ok((_ast, None)) => {
                         // println(format!(
                         //     "[PARSED] [{context}] Parsed file at path {rel_path:?}"
                         // ));
                     }
                     Err(e) => {
-                        let pkg_name = context.pkg_name;
-                        println(format!("[FAILED] [{pkg_name}] {e}"));
+                        println(format!("[FAILED] [{}] {e}", context.label()));
                     }
                 }
             }
@@ -0,0 +1,72 @@
+use std::{
+    collections::HashSet,
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use dart_parser::{pub_cache_dir, PackageSource, PubspecLock};
+
+use super::find_dart_files::FileContext;
+
+/// Locate the dependencies of the packages at `pkg_dirs` through their
+/// `pubspec.lock` files and the local pub cache, without any network access.
+///
+/// Every dependency is returned once, the ones that cannot be located
+/// (e.g. not downloaded yet) as errors. The SDK packages are skipped.
+pub fn find_deps(pkg_dirs: &[PathBuf]) -> Vec<Result<FileContext, String>> {
+    let pub_cache = pub_cache_dir(|name| env::var(name).ok());
+    let mut seen = pkg_dirs.iter().map(canonical).collect::<HashSet<_>>();
+    let mut deps = Vec::new();
+
+    for pkg_dir in pkg_dirs {
+        let lock_path = pkg_dir.join("pubspec.lock");
+        let lock = match fs::read_to_string(&lock_path) {
+            Ok(yaml) => PubspecLock::parse(&yaml, &lock_path),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => Err(format!("Cannot read file at path {lock_path:?}: {e}")),
+        };
+        let lock = match lock {
+            Ok(lock) => lock,
+            Err(e) => {
+                deps.push(Err(e));
+                continue;
+            }
+        };
+
+        for package in lock.packages {
+            if matches!(package.source, PackageSource::Sdk(_)) {
+                continue;
+            }
+
+            let root = match (&package.source, &pub_cache) {
+                (PackageSource::Path(_), _) => package.root(Path::new(""), Path::is_dir),
+                (_, Some(pub_cache)) => package.root(pub_cache, Path::is_dir),
+                (_, None) => None,
+            };
+            match root {
+                Some(root) => {
+                    if seen.insert(canonical(&root)) {
+                        deps.push(Ok(FileContext {
+                            pkg_name: package.name,
+                            pkg_dir: root,
+                            read_only: true,
+                        }));
+                    }
+                }
+                None => deps.push(Err(format!(
+                    "Cannot locate the dependency {:?} of the package at path {pkg_dir:?}, \
+                     run `dart pub get` first",
+                    package.name
+                ))),
+            }
+        }
+    }
+
+    deps
+}
+
+fn canonical(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_owned())
+}
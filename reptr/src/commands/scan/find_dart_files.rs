@@ -6,6 +6,20 @@ use crate::common::{MapDirResult, ReadDirExt};
 pub struct FileContext {
     pub pkg_name: String,
     pub pkg_dir: path::PathBuf,
+    /// Dependency packages are only parsed (e.g. to look up the types
+    /// declared there), code is never generated for them.
+    pub read_only: bool,
+}
+
+impl FileContext {
+    /// The package name, marked if it's a dependency.
+    pub fn label(&self) -> String {
+        if self.read_only {
+            format!("{} (dependency)", self.pkg_name)
+        } else {
+            self.pkg_name.clone()
+        }
+    }
 }
 
 pub fn find_dart_files(
@@ -37,6 +51,7 @@ pub fn find_dart_files(
                         MapDirResult::Mark(FileContext {
                             pkg_name: dir_name.to_owned(),
                             pkg_dir: path.to_owned(),
+                            read_only: false,
                         })
                     } else {
                         MapDirResult::Clear
@@ -47,22 +62,51 @@ pub fn find_dart_files(
                 Err(_) => Ok(MapDirResult::Clear),
             }
         },
-        |context, path| {
-            Ok(context.and_then(|context| {
-                if path
-                    .extension()
-                    .and_then(|s| s.to_str())
-                    .is_some_and(|ext| ext == "dart")
-                {
-                    Some((context.clone(), path))
-                } else {
-                    None
-                }
-            }))
+        dart_file,
+    )
+}
+
+/// Find the Dart files of a dependency package.
+///
+/// Only the `lib` directory is scanned, since the other files
+/// of a dependency cannot be imported.
+pub fn find_dep_dart_files(
+    context: FileContext,
+) -> impl Iterator<Item = io::Result<(FileContext, path::PathBuf)>> {
+    ReadDirExt::with_context(
+        context.pkg_dir.join("lib"),
+        context,
+        |context: Option<&FileContext>, path| {
+            let is_dot_dir = path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .is_some_and(|name| name.starts_with('.'));
+            match context {
+                Some(context) if !is_dot_dir => Ok(MapDirResult::Mark(context.to_owned())),
+                _ => Ok(MapDirResult::Ignore),
+            }
         },
+        dart_file,
     )
 }
 
+fn dart_file(
+    context: Option<&FileContext>,
+    path: path::PathBuf,
+) -> io::Result<Option<(FileContext, path::PathBuf)>> {
+    Ok(context.and_then(|context| {
+        if path
+            .extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|ext| ext == "dart")
+        {
+            Some((context.clone(), path))
+        } else {
+            None
+        }
+    }))
+}
+
 fn file_name(p: impl AsRef<path::Path>) -> io::Result<String> {
     fn cannot_convert_to_utf8() -> io::Error {
         io::Error::new(io::ErrorKind::InvalidData, "Cannot convert path to UTF-8")
@@ -1,38 +1,76 @@
-use std::{fmt::Display, string::FromUtf8Error, sync::Arc};
+use std::{fmt::Display, sync::Arc};
 
-use dart_parser::{Dart, WithMeta};
+use dart_parser::{validate, LineCol, LineIndex, ParseError, ParsedSource};
 
-use crate::common::SourceView;
+use crate::common::{decode_source, DecodedSource, InvalidUtf8};
 
 use super::{stats, time};
 
+/// A parsed source with a warning about its encoding or its declarations,
+/// if any.
+pub type Parsed = (ParsedSource, Option<String>);
+
 pub async fn parse_raw_async<Counter>(
     source: Vec<u8>,
+    latin1_fallback: bool,
+    read_only: bool,
     stats: Arc<std::sync::Mutex<Counter>>,
-) -> Result<SourceView<Vec<WithMeta<'static, Dart<'static>>>>, ParseRawError>
+) -> Result<Parsed, ParseRawError>
 where
     Counter: stats::Counter<stats::event::FileParsed> + Send + 'static,
 {
     let (ch_sink, ch_source) = tokio::sync::oneshot::channel();
 
     rayon::spawn(move || {
-        let ast = parse_raw(source, stats);
+        let ast = parse_raw(source, latin1_fallback, read_only, stats);
         ch_sink.send(ast).unwrap();
     });
 
     ch_source.await.unwrap()
 }
 
+/// The declarations that break the rules of the language are errors,
+/// unless the source is `read_only`, i.e. belongs to a dependency,
+/// in which case they are only reported as warnings.
 pub fn parse_raw<Counter>(
     source: Vec<u8>,
+    latin1_fallback: bool,
+    read_only: bool,
     stats: Arc<std::sync::Mutex<Counter>>,
-) -> Result<SourceView<Vec<WithMeta<'static, Dart<'static>>>>, ParseRawError>
+) -> Result<Parsed, ParseRawError>
 where
     Counter: stats::Counter<stats::event::FileParsed>,
 {
-    let (source, utf8_validation_duration) = time! { String::from_utf8(source)? };
+    let (DecodedSource { source, warning }, utf8_validation_duration) =
+        time! { decode_source(source, latin1_fallback)? };
+
+    let (ast, parsing_duration) = time! { ParsedSource::parse(source)? };
 
-    let (ast, parsing_duration) = time! { SourceView::try_new(source, dart_parser::parse)? };
+    let diagnostics = validate(ast.source(), ast.ast());
+    let warning = if diagnostics.is_empty() {
+        warning
+    } else {
+        let line_index = LineIndex::new(ast.source());
+        let messages = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let LineCol { line, col } = line_index
+                    .line_col_utf16(diagnostic.range.start)
+                    .unwrap_or(LineCol { line: 0, col: 0 });
+                format!("Line {}, column {}: {diagnostic}", line + 1, col + 1)
+            })
+            .collect::<Vec<_>>();
+        if !read_only {
+            return Err(ParseRawError::Invalid(messages));
+        }
+        Some(
+            warning
+                .into_iter()
+                .chain(messages)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    };
 
     let event = stats::event::FileParsed {
         size: ast.source().len(),
@@ -41,14 +79,16 @@ where
     };
     stats.lock()?.count(event);
 
-    Ok(ast)
+    Ok((ast, warning))
 }
 
 #[derive(Debug)]
 pub enum ParseRawError {
     SyncPoisoned,
-    InvalidUtf8(FromUtf8Error),
-    ParseError(String),
+    InvalidUtf8(InvalidUtf8),
+    ParseError(ParseError),
+    /// The source parses, but declarations break the rules of the language.
+    Invalid(Vec<String>),
 }
 
 impl Display for ParseRawError {
@@ -57,6 +97,7 @@ impl Display for ParseRawError {
             ParseRawError::SyncPoisoned => f.write_str("A synchronization primitive is poisoned"),
             ParseRawError::InvalidUtf8(e) => e.fmt(f),
             ParseRawError::ParseError(e) => e.fmt(f),
+            ParseRawError::Invalid(messages) => f.write_str(&messages.join("\n")),
         }
     }
 }
@@ -67,14 +108,14 @@ impl<T> From<std::sync::PoisonError<T>> for ParseRawError {
     }
 }
 
-impl From<FromUtf8Error> for ParseRawError {
-    fn from(value: FromUtf8Error) -> Self {
+impl From<InvalidUtf8> for ParseRawError {
+    fn from(value: InvalidUtf8) -> Self {
         ParseRawError::InvalidUtf8(value)
     }
 }
 
-impl From<String> for ParseRawError {
-    fn from(value: String) -> Self {
+impl From<ParseError> for ParseRawError {
+    fn from(value: ParseError) -> Self {
         ParseRawError::ParseError(value)
     }
 }
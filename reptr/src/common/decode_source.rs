@@ -0,0 +1,107 @@
+use std::fmt::Display;
+
+use dart_parser::{LineCol, LineIndex};
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+pub struct DecodedSource {
+    pub source: String,
+    /// Set when the source is not valid UTF-8 and has been decoded as Latin-1.
+    pub warning: Option<String>,
+}
+
+/// The location of an invalid UTF-8 sequence (one-based, the column is counted
+/// in UTF-16 code units like in most editors).
+#[derive(Debug)]
+pub struct InvalidUtf8 {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Display for InvalidUtf8 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid UTF-8 at line {}, column {}",
+            self.line, self.col
+        )
+    }
+}
+
+impl std::error::Error for InvalidUtf8 {}
+
+/// Decode a source file as UTF-8, skipping the byte-order mark.
+///
+/// Optionally, fall back to Latin-1 for legacy files that are not valid UTF-8.
+pub fn decode_source(
+    mut bytes: Vec<u8>,
+    latin1_fallback: bool,
+) -> Result<DecodedSource, InvalidUtf8> {
+    if bytes.starts_with(UTF8_BOM) {
+        bytes.drain(..UTF8_BOM.len());
+    }
+
+    match String::from_utf8(bytes) {
+        Ok(source) => Ok(DecodedSource {
+            source,
+            warning: None,
+        }),
+        Err(err) => {
+            let location = locate(err.as_bytes(), err.utf8_error().valid_up_to());
+            if latin1_fallback {
+                Ok(DecodedSource {
+                    source: err.as_bytes().iter().map(|&c| c as char).collect(),
+                    warning: Some(format!("{location}, decoded as Latin-1")),
+                })
+            } else {
+                Err(location)
+            }
+        }
+    }
+}
+
+fn locate(bytes: &[u8], offset: usize) -> InvalidUtf8 {
+    // The part before the invalid sequence is valid
+    let valid = std::str::from_utf8(&bytes[..offset]).unwrap_or_default();
+    let LineCol { line, col } = LineIndex::new(valid)
+        .line_col_utf16(offset)
+        .unwrap_or(LineCol { line: 0, col: 0 });
+
+    InvalidUtf8 {
+        line: line + 1,
+        col: col + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_source_bom_test() {
+        let source = decode_source(b"\xEF\xBB\xBFclass A {}".to_vec(), false).unwrap();
+
+        assert_eq!(source.source, "class A {}");
+        assert_eq!(source.warning, None);
+    }
+
+    #[test]
+    fn decode_source_invalid_test() {
+        // The column is counted in UTF-16 code units
+        let bytes = [
+            b"// \xC3\xA9\xF0\x9F\x98\x80\nvar s = 'caf".as_slice(),
+            b"\xE9';",
+        ]
+        .concat();
+
+        let err = decode_source(bytes.clone(), false).err().unwrap();
+        assert_eq!((err.line, err.col), (2, 13));
+
+        let source = decode_source(bytes, true).unwrap();
+        assert!(source.source.ends_with("var s = 'caf\u{e9}';"));
+        assert_eq!(
+            source.warning.as_deref(),
+            Some("Invalid UTF-8 at line 2, column 13, decoded as Latin-1")
+        );
+    }
+}
@@ -26,6 +26,16 @@ where
         }
     }
 
+    /// Start with `context` for `dir` instead of mapping it with `map_dir`.
+    pub fn with_context(dir: PathBuf, context: Context, map_dir: F, map_file: G) -> Self {
+        Self {
+            map_dir,
+            map_file,
+            q: vec![(Some(context), dir)],
+            current: None,
+        }
+    }
+
     fn next_io_result(&mut self) -> io::Result<Option<Item>> {
         loop {
             if let Some((context, read_dir)) = self.current.as_mut() {
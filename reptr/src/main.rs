@@ -26,6 +26,8 @@ async fn main() -> io::Result<()> {
         dirs: Vec::new(),
         ignore: Vec::new(),
         quiet: false,
+        latin1: false,
+        deps: false,
     });
 
     match cmd {
@@ -33,8 +35,21 @@ async fn main() -> io::Result<()> {
             dirs,
             ignore,
             quiet,
-        } => scan_dirs(dirs, scan::Options { ignore, quiet }).await?,
-        RunCmd::Parse { file } => parse_file(file)?,
+            latin1,
+            deps,
+        } => {
+            scan_dirs(
+                dirs,
+                scan::Options {
+                    ignore,
+                    quiet,
+                    latin1,
+                    deps,
+                },
+            )
+            .await?
+        }
+        RunCmd::Parse { file, latin1 } => parse_file(file, latin1)?,
     }
 
     Ok(())
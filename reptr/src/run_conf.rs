@@ -23,8 +23,18 @@ pub enum RunCmd {
         ignore: Vec<PathBuf>,
         #[arg(short, long)]
         quiet: bool,
+        /// Decode the files that are not valid UTF-8 as Latin-1.
+        #[arg(long)]
+        latin1: bool,
+        /// Parse the dependencies too, located through `pubspec.lock`
+        /// and the local pub cache (without any network access).
+        #[arg(long)]
+        deps: bool,
     },
     Parse {
         file: PathBuf,
+        /// Decode the file as Latin-1 if it's not valid UTF-8.
+        #[arg(long)]
+        latin1: bool,
     },
 }